@@ -64,6 +64,8 @@ pub struct OsrApp {
     cache_size_mb: i32,
     /// Custom command-line switches
     custom_switches: Vec<String>,
+    /// Project-defined custom URL schemes (in addition to `res` and `user`)
+    custom_schemes: Vec<String>,
 }
 
 impl Default for OsrApp {
@@ -85,6 +87,7 @@ impl OsrApp {
             proxy_bypass_list: String::new(),
             cache_size_mb: 0,
             custom_switches: Vec::new(),
+            custom_schemes: Vec::new(),
         }
     }
 
@@ -131,6 +134,10 @@ impl OsrApp {
     pub fn custom_switches(&self) -> &[String] {
         &self.custom_switches
     }
+
+    pub fn custom_schemes(&self) -> &[String] {
+        &self.custom_schemes
+    }
 }
 
 pub struct OsrAppBuilder {
@@ -200,6 +207,11 @@ impl OsrAppBuilder {
         self
     }
 
+    pub fn custom_schemes(mut self, custom_schemes: Vec<String>) -> Self {
+        self.inner.custom_schemes = custom_schemes;
+        self
+    }
+
     pub fn build(self) -> OsrApp {
         self.inner
     }
@@ -6,26 +6,33 @@ use cef::{
 };
 
 use crate::app::{GpuDeviceIds, SecurityConfig};
+use crate::schemes::CUSTOM_SCHEMES_SWITCH;
 
 #[derive(Clone)]
 pub struct OsrBrowserProcessHandler {
     is_cef_ready: RefCell<bool>,
     security_config: SecurityConfig,
     gpu_device_ids: Option<GpuDeviceIds>,
+    custom_schemes: Vec<String>,
 }
 
 impl Default for OsrBrowserProcessHandler {
     fn default() -> Self {
-        Self::new(SecurityConfig::default(), None)
+        Self::new(SecurityConfig::default(), None, Vec::new())
     }
 }
 
 impl OsrBrowserProcessHandler {
-    pub fn new(security_config: SecurityConfig, gpu_device_ids: Option<GpuDeviceIds>) -> Self {
+    pub fn new(
+        security_config: SecurityConfig,
+        gpu_device_ids: Option<GpuDeviceIds>,
+        custom_schemes: Vec<String>,
+    ) -> Self {
         Self {
             is_cef_ready: RefCell::new(false),
             security_config,
             gpu_device_ids,
+            custom_schemes,
         }
     }
 }
@@ -70,6 +77,15 @@ wrap_browser_process_handler! {
                     Some(&ids.to_device_arg().as_str().into()),
                 );
             }
+
+            // Subprocesses must register the same custom schemes as the browser process
+            if !self.handler.custom_schemes.is_empty() {
+                let schemes = self.handler.custom_schemes.join(",");
+                command_line.append_switch_with_value(
+                    Some(&CUSTOM_SCHEMES_SWITCH.into()),
+                    Some(&schemes.as_str().into()),
+                );
+            }
         }
     }
 }
@@ -0,0 +1,107 @@
+//! Collects the class names and ids used in a frame's DOM.
+//!
+//! The browser process matches them against generic adblock rules such as
+//! `##.ad-banner`, which cannot be looked up by URL alone.
+
+use std::collections::BTreeSet;
+
+use cef::{
+    CefStringUtf16, Domdocument, Domnode, Domvisitor, Frame, ImplDomdocument, ImplDomnode,
+    ImplDomvisitor, ImplFrame, ImplListValue, ImplProcessMessage, ProcessId, WrapDomvisitor,
+    process_message_create, rc::Rc, wrap_domvisitor,
+};
+
+use crate::ipc_contract::ROUTE_ADBLOCK_CLASS_IDS;
+
+#[derive(Default)]
+struct ClassIds {
+    classes: BTreeSet<String>,
+    ids: BTreeSet<String>,
+}
+
+impl ClassIds {
+    fn add(&mut self, class_attribute: &str, id_attribute: &str) {
+        self.classes
+            .extend(class_attribute.split_whitespace().map(str::to_string));
+        let id = id_attribute.trim();
+        if !id.is_empty() {
+            self.ids.insert(id.to_string());
+        }
+    }
+}
+
+fn attribute(node: &Domnode, name: &str) -> String {
+    CefStringUtf16::from(&node.element_attribute(Some(&name.into()))).to_string()
+}
+
+fn collect(root: Domnode) -> ClassIds {
+    let mut found = ClassIds::default();
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        if node.is_element() != 0 {
+            found.add(&attribute(&node, "class"), &attribute(&node, "id"));
+        }
+        let mut child = node.first_child();
+        while let Some(node) = child {
+            child = node.next_sibling();
+            stack.push(node);
+        }
+    }
+    found
+}
+
+wrap_domvisitor! {
+    pub(crate) struct ClassIdVisitorBuilder {
+        frame: Frame,
+    }
+
+    impl Domvisitor {
+        fn visit(&self, document: Option<&mut Domdocument>) {
+            let Some(root) = document.and_then(|document| document.document()) else {
+                return;
+            };
+            let found = collect(root);
+            if found.classes.is_empty() && found.ids.is_empty() {
+                return;
+            }
+
+            let route = CefStringUtf16::from(ROUTE_ADBLOCK_CLASS_IDS);
+            let Some(mut message) = process_message_create(Some(&route)) else {
+                return;
+            };
+            if let Some(args) = message.argument_list() {
+                let classes = found.classes.into_iter().collect::<Vec<_>>().join(" ");
+                let ids = found.ids.into_iter().collect::<Vec<_>>().join(" ");
+                args.set_string(0, Some(&classes.as_str().into()));
+                args.set_string(1, Some(&ids.as_str().into()));
+            }
+            self.frame
+                .send_process_message(ProcessId::BROWSER, Some(&mut message));
+        }
+    }
+}
+
+impl ClassIdVisitorBuilder {
+    pub(crate) fn build(frame: Frame) -> Domvisitor {
+        Self::new(frame)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_class_ids_split_and_dedupe() {
+        let mut found = ClassIds::default();
+        found.add(" ad  banner\tad ", " top ");
+        found.add("", "");
+        found.add("banner", "top");
+
+        assert_eq!(
+            found.classes.into_iter().collect::<Vec<_>>(),
+            ["ad", "banner"]
+        );
+        assert_eq!(found.ids.into_iter().collect::<Vec<_>>(), ["top"]);
+    }
+}
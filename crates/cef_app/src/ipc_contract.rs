@@ -17,3 +17,8 @@ pub const ROUTE_IPC_DATA_RENDERER_TO_GODOT: &str = "ipcDataRendererToGodot";
 
 pub const ROUTE_TRIGGER_IME: &str = "triggerIme";
 pub const ROUTE_IME_CARET_POSITION: &str = "imeCaretPosition";
+
+/// Asks the renderer for the class names and ids in a frame's DOM.
+pub const ROUTE_ADBLOCK_COLLECT_CLASS_IDS: &str = "adblockCollectClassIds";
+/// Class names and ids of a frame's DOM, each a space-separated string.
+pub const ROUTE_ADBLOCK_CLASS_IDS: &str = "adblockClassIds";
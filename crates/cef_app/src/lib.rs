@@ -1,15 +1,18 @@
 mod app;
 mod browser_process;
+mod dom_class_ids;
 pub mod ipc_contract;
 mod loader;
 mod render_handler;
 mod render_process;
+mod schemes;
 mod types;
 mod v8_handlers;
 
 pub use app::{GodotRenderBackend, GpuDeviceIds, OsrApp, OsrAppBuilder, SecurityConfig};
 pub use loader::{load_cef_framework_from_path, load_sandbox_from_path};
 pub use render_handler::OsrRenderHandler;
+pub use schemes::{CUSTOM_SCHEMES_SWITCH, GODOT_SCHEMES, parse_custom_schemes};
 pub use types::{CursorType, FrameBuffer, PhysicalSize, PopupRect, PopupState};
 
 use crate::browser_process::{BrowserProcessHandlerBuilder, OsrBrowserProcessHandler};
@@ -34,15 +37,13 @@ wrap_app! {
                 | cef::SchemeOptions::FETCH_ENABLED.get_raw()
                 | cef::SchemeOptions::CSP_BYPASSING.get_raw();
 
-            #[cfg(target_os = "windows")]
-            {
-                registrar.add_custom_scheme(Some(&"res".into()), options);
-                registrar.add_custom_scheme(Some(&"user".into()), options);
-            }
-            #[cfg(not(target_os = "windows"))]
-            {
-                registrar.add_custom_scheme(Some(&"res".into()), options as i32);
-                registrar.add_custom_scheme(Some(&"user".into()), options as i32);
+            // Project-defined schemes are registered with the same options as res:// and user://
+            let custom_schemes = self.app.custom_schemes().iter().map(String::as_str);
+            for scheme in GODOT_SCHEMES.into_iter().chain(custom_schemes) {
+                #[cfg(target_os = "windows")]
+                registrar.add_custom_scheme(Some(&scheme.into()), options);
+                #[cfg(not(target_os = "windows"))]
+                registrar.add_custom_scheme(Some(&scheme.into()), options as i32);
             }
         }
 
@@ -128,6 +129,7 @@ wrap_app! {
                 OsrBrowserProcessHandler::new(
                     self.app.security_config().clone(),
                     self.app.gpu_device_ids(),
+                    self.app.custom_schemes().to_vec(),
                 ),
             ))
         }
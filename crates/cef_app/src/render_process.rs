@@ -9,9 +9,10 @@ use cef::{
     wrap_render_process_handler,
 };
 
+use crate::dom_class_ids::ClassIdVisitorBuilder;
 use crate::ipc_contract::{
-    ROUTE_IPC_BINARY_GODOT_TO_RENDERER, ROUTE_IPC_DATA_GODOT_TO_RENDERER,
-    ROUTE_IPC_GODOT_TO_RENDERER, ROUTE_TRIGGER_IME,
+    ROUTE_ADBLOCK_COLLECT_CLASS_IDS, ROUTE_IPC_BINARY_GODOT_TO_RENDERER,
+    ROUTE_IPC_DATA_GODOT_TO_RENDERER, ROUTE_IPC_GODOT_TO_RENDERER, ROUTE_TRIGGER_IME,
 };
 use crate::v8_handlers::{
     IpcListenerSet, OsrImeCaretHandler, OsrImeCaretHandlerBuilder, OsrIpcBinaryHandler,
@@ -166,6 +167,13 @@ wrap_render_process_handler! {
                     }
                     return 1;
                 }
+                ROUTE_ADBLOCK_COLLECT_CLASS_IDS => {
+                    if let Some(frame) = frame {
+                        let mut visitor = ClassIdVisitorBuilder::build(frame.clone());
+                        frame.visit_dom(Some(&mut visitor));
+                    }
+                    return 1;
+                }
                 _ => {}
             }
 
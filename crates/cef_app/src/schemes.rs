@@ -0,0 +1,111 @@
+/// Command-line switch carrying project-defined custom schemes to CEF subprocesses.
+///
+/// Schemes must be registered identically in every process, but subprocesses
+/// cannot read Godot project settings, so the browser process forwards them.
+pub const CUSTOM_SCHEMES_SWITCH: &str = "godot-custom-schemes";
+
+/// Schemes that are built in to Chromium or already served by Godot CEF.
+const RESERVED_SCHEMES: &[&str] = &[
+    "res",
+    "user",
+    "http",
+    "https",
+    "ws",
+    "wss",
+    "ftp",
+    "file",
+    "data",
+    "blob",
+    "about",
+    "javascript",
+    "filesystem",
+    "mailto",
+    "chrome",
+    "chrome-extension",
+    "chrome-untrusted",
+    "devtools",
+    "view-source",
+];
+
+/// Schemes registered for the built-in Godot filesystem handlers.
+pub const GODOT_SCHEMES: [&str; 2] = ["res", "user"];
+
+fn is_valid_scheme_name(scheme: &str) -> bool {
+    let mut chars = scheme.chars();
+    chars.next().is_some_and(|c| c.is_ascii_lowercase())
+        && chars
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '+' | '-' | '.'))
+}
+
+/// Parses a comma- or newline-separated scheme list into normalized names.
+///
+/// Names are lowercased and stripped of a trailing `:` or `://`. Invalid,
+/// reserved and duplicate entries are dropped.
+pub fn parse_custom_schemes(raw: &str) -> Vec<String> {
+    let mut schemes: Vec<String> = Vec::new();
+
+    for entry in raw.split([',', '\n']) {
+        let scheme = entry
+            .trim()
+            .trim_end_matches("://")
+            .trim_end_matches(':')
+            .to_ascii_lowercase();
+
+        if !is_valid_scheme_name(&scheme)
+            || RESERVED_SCHEMES.contains(&scheme.as_str())
+            || schemes.contains(&scheme)
+        {
+            continue;
+        }
+        schemes.push(scheme);
+    }
+
+    schemes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_custom_schemes_normalizes() {
+        assert_eq!(
+            parse_custom_schemes("App, game-ui://\nMods:"),
+            vec!["app", "game-ui", "mods"]
+        );
+        assert_eq!(
+            parse_custom_schemes("web+app, x.y-1"),
+            vec!["web+app", "x.y-1"]
+        );
+    }
+
+    #[test]
+    fn test_parse_custom_schemes_skips_reserved() {
+        assert_eq!(
+            parse_custom_schemes("http, HTTPS, res, user://, app"),
+            vec!["app"]
+        );
+        assert_eq!(
+            parse_custom_schemes("chrome-extension, devtools"),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn test_parse_custom_schemes_skips_invalid() {
+        assert_eq!(
+            parse_custom_schemes("1app, -app, my app, ap_p, app/x, app?, game"),
+            vec!["game"]
+        );
+    }
+
+    #[test]
+    fn test_parse_custom_schemes_skips_duplicates_and_empty() {
+        assert_eq!(
+            parse_custom_schemes("app, APP, app://, game"),
+            vec!["app", "game"]
+        );
+        assert_eq!(parse_custom_schemes(""), Vec::<String>::new());
+        assert_eq!(parse_custom_schemes(" , ,\n\n://, :"), Vec::<String>::new());
+    }
+}
@@ -0,0 +1,237 @@
+//! Adblock engine shared by every browser.
+//!
+//! The engine is compiled from the rule lists in
+//! `godot_cef/network/adblock_rules_path` once per process and shared through
+//! an `Arc`. The compiled engine is also written to the data path together
+//! with a hash of the rule lists, so later startups deserialize it instead of
+//! parsing the lists again. Editing a list changes the hash and recompiles it.
+//!
+//! A deserialized engine has no rule text, so `request_blocked` reports an
+//! empty rule for it. Editor and debug runs, and `reload_adblock_rules()`,
+//! always compile the lists to keep the rule text.
+//!
+//! Scriptlet rules (`##+js`) need the resources in
+//! `godot_cef/network/adblock_resources_path`, a JSON array in the format of
+//! adblock-rust's `Resource`. They are not part of the cache and are loaded
+//! into every engine that is built or deserialized.
+
+use adblock::lists::{FilterSet, ParseOptions};
+use adblock::resources::Resource;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, RwLock};
+
+/// The shared engine; `reload_adblock_rules()` swaps the engine in place.
+pub(crate) type AdblockEngineHandle = Arc<RwLock<adblock::Engine>>;
+
+static SHARED_ENGINE: Mutex<Option<AdblockEngineHandle>> = Mutex::new(None);
+
+// Browsers use the engine from CEF's IO and UI threads; this stops compiling if
+// `adblock` is built with its `single-thread` feature.
+const _: fn() = || {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<adblock::Engine>();
+};
+
+const CACHE_FILE_NAME: &str = "adblock-engine.dat";
+/// Prefix of the cache file; bump when the layout after it changes.
+const CACHE_MAGIC: &[u8; 8] = b"GCEFADB1";
+
+/// Hashes the rule lists with FNV-1a, which unlike `DefaultHasher` is stable across builds.
+fn rules_hash(lists: &[String]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for list in lists {
+        for &byte in list.as_bytes().iter().chain(&[0xff]) {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+    hash
+}
+
+/// Returns the serialized engine stored in `cache` if it was compiled from rules with `hash`.
+fn cached_engine_bytes(cache: &[u8], hash: u64) -> Option<&[u8]> {
+    let rest = cache.strip_prefix(CACHE_MAGIC.as_slice())?;
+    let (stored_hash, engine) = rest.split_first_chunk::<8>()?;
+    (u64::from_le_bytes(*stored_hash) == hash).then_some(engine)
+}
+
+fn cache_path() -> PathBuf {
+    crate::settings::get_data_path().join(CACHE_FILE_NAME)
+}
+
+/// Reads every configured rule list, skipping the ones that cannot be read.
+fn read_rule_lists(log_prefix: &str) -> Vec<String> {
+    let rules_paths = crate::settings::get_adblock_rules_paths();
+    if rules_paths.is_empty() {
+        godot::global::godot_warn!(
+            "[{}] Adblock is enabled, but adblock rules path setting is empty. Request filtering will be disabled.",
+            log_prefix
+        );
+    }
+
+    rules_paths
+        .iter()
+        .filter_map(|rules_path| match fs::read_to_string(rules_path) {
+            Ok(rules) => Some(rules),
+            Err(error) => {
+                godot::global::godot_warn!(
+                    "[{}] Failed to read adblock rules file '{}': {}. Its rules will not be applied.",
+                    log_prefix,
+                    rules_path.display().to_string(),
+                    error
+                );
+                None
+            }
+        })
+        .collect()
+}
+
+/// Reads the scriptlet resources file, or an empty list when it is unset or unreadable.
+fn read_resources(log_prefix: &str) -> Vec<Resource> {
+    let Some(resources_path) = crate::settings::get_adblock_resources_path() else {
+        return Vec::new();
+    };
+    let resources = fs::read(&resources_path)
+        .map_err(|error| error.to_string())
+        .and_then(|json| serde_json::from_slice(&json).map_err(|error| error.to_string()));
+    match resources {
+        Ok(resources) => resources,
+        Err(error) => {
+            godot::global::godot_warn!(
+                "[{}] Failed to read adblock resources file '{}': {}. Scriptlet rules will not be applied.",
+                log_prefix,
+                resources_path.display().to_string(),
+                error
+            );
+            Vec::new()
+        }
+    }
+}
+
+fn load_cached_engine(hash: u64) -> Option<adblock::Engine> {
+    let cache = fs::read(cache_path()).ok()?;
+    let serialized = cached_engine_bytes(&cache, hash)?;
+    let mut engine = adblock::Engine::from_filter_set(FilterSet::new(true), true);
+    engine.deserialize(serialized).ok()?;
+    Some(engine)
+}
+
+fn store_cached_engine(engine: &adblock::Engine, hash: u64, log_prefix: &str) {
+    let mut cache = Vec::from(CACHE_MAGIC.as_slice());
+    cache.extend_from_slice(&hash.to_le_bytes());
+    cache.extend_from_slice(&engine.serialize());
+
+    let path = cache_path();
+    let result = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|()| fs::write(&path, cache));
+    if let Err(error) = result {
+        godot::global::godot_warn!(
+            "[{}] Failed to cache the adblock engine at '{}': {}",
+            log_prefix,
+            path.display().to_string(),
+            error
+        );
+    }
+}
+
+/// Builds an engine from the current rule lists and scriptlet resources.
+fn build_engine(log_prefix: &str, use_cache: bool) -> adblock::Engine {
+    let mut engine = compile_engine(log_prefix, use_cache);
+    engine.use_resources(read_resources(log_prefix));
+    engine
+}
+
+/// Compiles the current rule lists and caches the result. With `use_cache`, a
+/// cached engine for the same lists is loaded instead.
+fn compile_engine(log_prefix: &str, use_cache: bool) -> adblock::Engine {
+    let lists = read_rule_lists(log_prefix);
+    let hash = rules_hash(&lists);
+    if use_cache && let Some(engine) = load_cached_engine(hash) {
+        godot::global::godot_print!("[{}] Adblock engine loaded from cache.", log_prefix);
+        return engine;
+    }
+
+    // Debug mode keeps the original rule text, reported by `request_blocked`
+    let mut filter_set = FilterSet::new(true);
+    for rules in &lists {
+        let _metadata = filter_set.add_filter_list(rules, ParseOptions::default());
+    }
+    let engine = adblock::Engine::from_filter_set(filter_set, true);
+    if !lists.is_empty() {
+        godot::global::godot_print!(
+            "[{}] Adblock loaded {} filter list(s).",
+            log_prefix,
+            lists.len()
+        );
+        store_cached_engine(&engine, hash, log_prefix);
+    }
+    engine
+}
+
+/// Returns the shared engine, building it on first use. Returns `None` when adblock is disabled.
+pub(crate) fn shared_engine(log_prefix: &str) -> Option<AdblockEngineHandle> {
+    if !crate::settings::is_adblock_enabled() {
+        return None;
+    }
+    let mut shared = SHARED_ENGINE.lock().ok()?;
+    let handle = shared.get_or_insert_with(|| {
+        let use_cache = !crate::utils::is_development_run();
+        Arc::new(RwLock::new(build_engine(log_prefix, use_cache)))
+    });
+    Some(handle.clone())
+}
+
+/// Re-reads the rule lists into the shared engine used by every browser.
+/// Returns `false` when adblock is disabled.
+pub(crate) fn reload_shared_engine(log_prefix: &str) -> bool {
+    if !crate::settings::is_adblock_enabled() {
+        return false;
+    }
+    let engine = build_engine(log_prefix, false);
+    let Ok(mut shared) = SHARED_ENGINE.lock() else {
+        return false;
+    };
+    if let Some(handle) = shared.as_ref() {
+        let Ok(mut current) = handle.write() else {
+            return false;
+        };
+        *current = engine;
+    } else {
+        *shared = Some(Arc::new(RwLock::new(engine)));
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rules_hash_depends_on_list_boundaries() {
+        let one = rules_hash(&["||ads.example^\n||track.example^".to_string()]);
+        let two = rules_hash(&[
+            "||ads.example^\n".to_string(),
+            "||track.example^".to_string(),
+        ]);
+        assert_ne!(one, two);
+        assert_eq!(
+            one,
+            rules_hash(&["||ads.example^\n||track.example^".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_cached_engine_bytes_checks_magic_and_hash() {
+        let mut cache = Vec::from(CACHE_MAGIC.as_slice());
+        cache.extend_from_slice(&42u64.to_le_bytes());
+        cache.extend_from_slice(b"engine");
+
+        assert_eq!(cached_engine_bytes(&cache, 42), Some(&b"engine"[..]));
+        assert_eq!(cached_engine_bytes(&cache, 43), None);
+        assert_eq!(cached_engine_bytes(b"GCEFADB1", 42), None);
+        assert_eq!(cached_engine_bytes(b"engine", 42), None);
+    }
+}
@@ -0,0 +1,165 @@
+//! HTTP and proxy authentication for CEF integration.
+//!
+//! `RequestHandler::get_auth_credentials` answers from the presets in
+//! `godot_cef/network/auth_credentials` first. Otherwise it queues an
+//! `AuthRequestEvent` and keeps CEF's callback until the node calls
+//! `provide_credentials()` or `cancel_auth()`.
+//!
+//! A preset is sent once per server or proxy and realm. If it is rejected, the
+//! next challenge goes to the node instead. Once a response from that server,
+//! or any response past the proxy, shows the credentials were accepted, the
+//! preset may be sent again, e.g. when a session expires.
+
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+/// A server or proxy asking for credentials.
+#[derive(Debug, Clone)]
+pub struct AuthRequestEvent {
+    pub request_id: i64,
+    pub host: String,
+    pub port: i32,
+    pub realm: String,
+    pub scheme: String,
+    pub is_proxy: bool,
+}
+
+/// Credentials configured for a host pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CredentialPreset {
+    host_pattern: String,
+    username: String,
+    password: String,
+}
+
+/// Parses `user:password@host[:port]`. The password may contain `:` and `@`.
+fn parse_preset(entry: &str) -> Option<CredentialPreset> {
+    let (credentials, host_pattern) = entry.rsplit_once('@')?;
+    let (username, password) = credentials.split_once(':')?;
+    if username.is_empty() || host_pattern.is_empty() {
+        return None;
+    }
+    Some(CredentialPreset {
+        host_pattern: host_pattern.to_string(),
+        username: username.to_string(),
+        password: password.to_string(),
+    })
+}
+
+fn find_preset(entries: &[String], host: &str, port: i32) -> Option<(String, String)> {
+    let host = host.to_ascii_lowercase();
+    let port = u16::try_from(port).ok();
+    entries
+        .iter()
+        .filter_map(|entry| {
+            let preset = parse_preset(entry);
+            if preset.is_none() {
+                godot::global::godot_warn!(
+                    "[CefTexture] Ignoring credential preset without 'user:password@host': '{}'",
+                    entry.rsplit_once('@').map_or("", |(_, host)| host)
+                );
+            }
+            preset
+        })
+        .find(|preset| crate::certificate::host_matches(&preset.host_pattern, &host, port))
+        .map(|preset| (preset.username, preset.password))
+}
+
+/// Presets already sent and not yet accepted, keyed by [`preset_attempt_key`].
+pub(crate) type TriedAuthPresets = Arc<Mutex<HashSet<String>>>;
+
+pub(crate) fn preset_attempt_key(is_proxy: bool, host: &str, port: i32, realm: &str) -> String {
+    let kind = if is_proxy { "proxy" } else { "server" };
+    format!("{} {}:{} {}", kind, host.to_ascii_lowercase(), port, realm)
+}
+
+/// Forgets the presets that a response with HTTP `status` from `host` and
+/// `port` shows were accepted.
+pub(crate) fn forget_accepted_presets(
+    tried: &mut HashSet<String>,
+    host: &str,
+    port: i32,
+    status: i32,
+) {
+    if status <= 0 || status == 407 {
+        return;
+    }
+    let server = preset_attempt_key(false, host, port, "");
+    tried.retain(|key| {
+        let proxy_accepted = key.starts_with("proxy ");
+        let server_accepted = status != 401 && key.starts_with(&server);
+        !proxy_accepted && !server_accepted
+    });
+}
+
+/// Returns the configured username and password for `host` and `port`.
+pub(crate) fn preset_credentials(host: &str, port: i32) -> Option<(String, String)> {
+    find_preset(&crate::settings::get_auth_credentials(), host, port)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_preset() {
+        assert_eq!(
+            parse_preset("kiosk:p@ss:word@intranet.local:8080"),
+            Some(CredentialPreset {
+                host_pattern: "intranet.local:8080".to_string(),
+                username: "kiosk".to_string(),
+                password: "p@ss:word".to_string(),
+            })
+        );
+        assert_eq!(parse_preset("kiosk@intranet.local"), None);
+        assert_eq!(parse_preset(":secret@intranet.local"), None);
+        assert_eq!(parse_preset("kiosk:secret@"), None);
+    }
+
+    #[test]
+    fn test_forget_accepted_presets() {
+        let tried = || -> HashSet<String> {
+            [
+                preset_attempt_key(true, "proxy.corp", 3128, "Squid"),
+                preset_attempt_key(false, "Intranet.local", 443, "Wiki"),
+                preset_attempt_key(false, "intranet.local", 8443, "Wiki"),
+            ]
+            .into_iter()
+            .collect()
+        };
+
+        let mut rejected = tried();
+        forget_accepted_presets(&mut rejected, "intranet.local", 443, 407);
+        forget_accepted_presets(&mut rejected, "intranet.local", 443, 0);
+        assert_eq!(rejected, tried());
+
+        let mut past_proxy = tried();
+        forget_accepted_presets(&mut past_proxy, "intranet.local", 443, 401);
+        assert_eq!(past_proxy.len(), 2);
+        assert!(!past_proxy.iter().any(|key| key.starts_with("proxy ")));
+
+        let mut accepted = tried();
+        forget_accepted_presets(&mut accepted, "intranet.local", 443, 200);
+        assert_eq!(
+            accepted.into_iter().collect::<Vec<_>>(),
+            [preset_attempt_key(false, "intranet.local", 8443, "Wiki")]
+        );
+    }
+
+    #[test]
+    fn test_find_preset() {
+        let entries = vec![
+            "proxy:one@proxy.corp:3128".to_string(),
+            "kiosk:two@*.intranet.local".to_string(),
+        ];
+        assert_eq!(
+            find_preset(&entries, "Proxy.Corp", 3128),
+            Some(("proxy".to_string(), "one".to_string()))
+        );
+        assert_eq!(find_preset(&entries, "proxy.corp", 8080), None);
+        assert_eq!(
+            find_preset(&entries, "wiki.intranet.local", 443),
+            Some(("kiosk".to_string(), "two".to_string()))
+        );
+    }
+}
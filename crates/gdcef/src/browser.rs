@@ -9,7 +9,7 @@ use godot::classes::{ImageTexture, Texture2D, Texture2Drd};
 use godot::prelude::*;
 use std::collections::{HashMap, VecDeque};
 use std::sync::atomic::{AtomicBool, AtomicI32, AtomicI64};
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, RwLock};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use crate::cookie::CookieEvent;
@@ -44,6 +44,38 @@ pub type PermissionPolicyFlag = Arc<AtomicI32>;
 /// Monotonic request-id counter for permission requests.
 pub type PermissionRequestIdCounter = Arc<AtomicI64>;
 
+/// URL and MIME type patterns of one response filter.
+#[derive(Clone, Debug)]
+pub struct ResponseFilterPattern {
+    pub url_pattern: String,
+    /// Matched against the response MIME type. Empty matches every type.
+    pub mime_filter: String,
+}
+
+impl ResponseFilterPattern {
+    /// Whether a response for `url` with the lowercase `mime_type` goes through this filter.
+    pub fn matches(&self, url: &str, mime_type: &str) -> bool {
+        crate::godot_protocol::glob_matches(&self.url_pattern, url)
+            && (self.mime_filter.is_empty()
+                || crate::godot_protocol::glob_matches(&self.mime_filter, mime_type))
+    }
+}
+
+/// Request hooks a node configures from GDScript, readable from the CEF IO thread.
+/// The callables themselves stay on the node and only run on the main thread.
+#[derive(Clone, Default)]
+pub struct RequestHooks {
+    /// URL patterns of the node's request interceptors.
+    pub interceptor_patterns: Arc<RwLock<Vec<String>>>,
+    /// Patterns of the node's response filters.
+    pub response_filters: Arc<RwLock<Vec<ResponseFilterPattern>>>,
+    /// Whether the node emits `resource_load_started` and `resource_load_completed`.
+    pub resource_events: Arc<AtomicBool>,
+}
+
+/// Response chosen by a request interceptor, picked up by the resource request handler.
+pub type InterceptedResponseSlot = Arc<Mutex<Option<crate::godot_protocol::CustomSchemeResponse>>>;
+
 /// Represents a loading state event from the browser.
 #[derive(Debug, Clone)]
 pub enum LoadingStateEvent {
@@ -59,6 +91,37 @@ pub enum LoadingStateEvent {
     },
 }
 
+/// A resource load reported to nodes with `enable_resource_events`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ResourceLoadEvent {
+    /// The request is about to be sent.
+    Started {
+        id: u64,
+        url: String,
+        resource_type: &'static str,
+        method: String,
+    },
+    /// The request finished, failed or was canceled.
+    Completed {
+        id: u64,
+        url: String,
+        status: i32,
+        mime_type: String,
+        received_bytes: i64,
+        duration_ms: i64,
+        /// Empty on success, otherwise `canceled` or `failed`.
+        error: String,
+    },
+}
+
+/// A request canceled by the adblock engine.
+#[derive(Debug, Clone)]
+pub struct BlockedRequestEvent {
+    pub url: String,
+    /// The filter rule that matched, as written in the rule list.
+    pub rule: String,
+}
+
 /// IME composition range info for caret positioning.
 #[derive(Clone, Copy, Debug)]
 pub struct ImeCompositionRange {
@@ -154,6 +217,73 @@ pub struct PermissionRequestEvent {
     pub request_id: i64,
 }
 
+/// A fetched response whose body waits for the node's response filters.
+pub struct FilteredResponseEvent {
+    pub url: String,
+    pub mime_type: String,
+    pub charset: String,
+    pub body: Vec<u8>,
+    /// Status and headers of the response, served once the body is decided.
+    response: Mutex<Option<crate::godot_protocol::CustomSchemeResponse>>,
+    deferred: crate::godot_protocol::DeferredResponse,
+}
+
+impl FilteredResponseEvent {
+    pub fn new(
+        url: String,
+        mime_type: String,
+        mut response: crate::godot_protocol::CustomSchemeResponse,
+        deferred: crate::godot_protocol::DeferredResponse,
+    ) -> Self {
+        let charset = response.charset();
+        let body = std::mem::take(&mut response.body);
+        Self {
+            url,
+            mime_type,
+            charset,
+            body,
+            response: Mutex::new(Some(response)),
+            deferred,
+        }
+    }
+
+    /// Serves the response with `body`. Returns `false` if it was already served.
+    pub fn complete(&self, body: Vec<u8>) -> bool {
+        let Some(mut response) = self.response.lock().ok().and_then(|mut slot| slot.take()) else {
+            return false;
+        };
+        response.body = body;
+        self.deferred.complete(response);
+        true
+    }
+}
+
+impl Drop for FilteredResponseEvent {
+    fn drop(&mut self) {
+        // The node was freed before it got to this event; the page still gets a response
+        let body = std::mem::take(&mut self.body);
+        if self.complete(body) {
+            godot::global::godot_warn!(
+                "[CefTexture] {} was served unfiltered because its node went away",
+                self.url
+            );
+        }
+    }
+}
+
+/// A request paused until the node's interceptors decide how to answer it.
+#[derive(Clone)]
+pub struct InterceptedRequestEvent {
+    pub method: String,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+    /// Receives the interceptor's response; left empty to pass the request through.
+    pub response: InterceptedResponseSlot,
+    /// Resumes the request once `response` is decided.
+    pub callback: cef::Callback,
+}
+
 #[derive(Debug, Clone)]
 pub struct FindResultEvent {
     pub count: i32,
@@ -200,6 +330,8 @@ pub struct DebugIpcEvent {
     pub body: String,
     pub timestamp_unix_ms: i64,
     pub body_size_bytes: i64,
+    /// CBOR-encoded payload for the `data` lane, used to render a structured view.
+    pub data: Option<Vec<u8>>,
 }
 
 impl DebugIpcEvent {
@@ -214,27 +346,27 @@ impl DebugIpcEvent {
         Self::new(direction, DebugIpcLane::Binary, body, body_size_bytes)
     }
 
-    pub fn data_from_variant(
-        direction: DebugIpcDirection,
-        data: &Variant,
-        body_size_bytes: usize,
-    ) -> Self {
+    pub fn data_from_variant(direction: DebugIpcDirection, data: &Variant, cbor: &[u8]) -> Self {
         let body = data.stringify().to_string();
-        Self::new(
+        let mut event = Self::new(
             direction,
             DebugIpcLane::Data,
             body,
-            saturating_usize_to_i64(body_size_bytes),
-        )
+            saturating_usize_to_i64(cbor.len()),
+        );
+        event.data = Some(cbor.to_vec());
+        event
     }
 
     pub fn data_from_cbor(direction: DebugIpcDirection, bytes: &[u8]) -> Self {
-        let body = match crate::ipc_data::decode_cbor_bytes_to_variant(bytes) {
-            Ok(data) => data.stringify().to_string(),
-            Err(err) => format!("<invalid cbor: {}>", err),
+        let (body, data) = match crate::ipc_data::decode_cbor_bytes_to_variant(bytes) {
+            Ok(data) => (data.stringify().to_string(), Some(bytes.to_vec())),
+            Err(err) => (format!("<invalid cbor: {}>", err), None),
         };
         let body_size_bytes = saturating_usize_to_i64(bytes.len());
-        Self::new(direction, DebugIpcLane::Data, body, body_size_bytes)
+        let mut event = Self::new(direction, DebugIpcLane::Data, body, body_size_bytes);
+        event.data = data;
+        event
     }
 
     fn new(
@@ -249,6 +381,55 @@ impl DebugIpcEvent {
             body,
             timestamp_unix_ms: unix_timestamp_ms(),
             body_size_bytes,
+            data: None,
+        }
+    }
+}
+
+/// Category of a non-IPC event shown in the inspector timeline.
+#[derive(Debug, Clone, Copy)]
+pub enum DebugTimelineKind {
+    Console,
+    Navigation,
+    Network,
+    Permission,
+    Popup,
+}
+
+impl DebugTimelineKind {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Console => "console",
+            Self::Navigation => "navigation",
+            Self::Network => "network",
+            Self::Permission => "permission",
+            Self::Popup => "popup",
+        }
+    }
+}
+
+/// A browser event captured for the inspector timeline.
+///
+/// Timestamped at capture time so entries can be correlated with `DebugIpcEvent`s.
+#[derive(Debug, Clone)]
+pub struct DebugTimelineEvent {
+    pub kind: DebugTimelineKind,
+    /// Short event name, e.g. `load_finished` or `console_message`.
+    pub name: String,
+    pub url: String,
+    /// Human-readable details such as status code, level or message text.
+    pub detail: String,
+    pub timestamp_unix_ms: i64,
+}
+
+impl DebugTimelineEvent {
+    pub fn new(kind: DebugTimelineKind, name: &str, url: String, detail: String) -> Self {
+        Self {
+            kind,
+            name: name.to_string(),
+            url,
+            detail,
+            timestamp_unix_ms: unix_timestamp_ms(),
         }
     }
 }
@@ -374,6 +555,65 @@ impl PendingPermissionAggregate {
 /// Per-callback aggregation state used to resolve multi-permission requests.
 pub type PendingPermissionAggregates = Arc<Mutex<HashMap<usize, PendingPermissionAggregate>>>;
 
+/// CEF callbacks waiting for a decision from GDScript, keyed by the request id
+/// passed to the signal. Dropping a callback without running it cancels the request.
+pub struct PendingCallbacks<T> {
+    next_id: Arc<AtomicI64>,
+    callbacks: Arc<Mutex<HashMap<i64, T>>>,
+}
+
+impl<T> Clone for PendingCallbacks<T> {
+    fn clone(&self) -> Self {
+        Self {
+            next_id: self.next_id.clone(),
+            callbacks: self.callbacks.clone(),
+        }
+    }
+}
+
+impl<T> Default for PendingCallbacks<T> {
+    fn default() -> Self {
+        Self {
+            next_id: Arc::new(AtomicI64::new(0)),
+            callbacks: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+}
+
+impl<T> PendingCallbacks<T> {
+    /// Stores `callback` and returns its request id.
+    pub fn insert(&self, callback: T) -> Option<i64> {
+        use std::sync::atomic::Ordering;
+        let request_id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        self.callbacks.lock().ok()?.insert(request_id, callback);
+        Some(request_id)
+    }
+
+    pub fn take(&self, request_id: i64) -> Option<T> {
+        self.callbacks.lock().ok()?.remove(&request_id)
+    }
+
+    pub fn clear(&self) {
+        if let Ok(mut callbacks) = self.callbacks.lock() {
+            callbacks.clear();
+        }
+    }
+
+    /// Removes and returns every stored callback.
+    pub fn drain(&self) -> Vec<T> {
+        self.callbacks
+            .lock()
+            .map(|mut callbacks| callbacks.drain().map(|(_, callback)| callback).collect())
+            .unwrap_or_default()
+    }
+}
+
+/// Certificate error callbacks waiting for `allow_certificate` or `deny_certificate`.
+pub type PendingCertificateErrors = PendingCallbacks<cef::Callback>;
+
+/// Authentication callbacks waiting for `provide_credentials` or `cancel_auth`.
+pub type PendingAuthRequests = PendingCallbacks<cef::AuthCallback>;
+
 /// Consolidated event queues for browser-to-Godot communication.
 ///
 /// All UI-thread callbacks write to this single structure, which is then
@@ -389,6 +629,8 @@ pub struct EventQueues {
     pub data_messages: VecDeque<Vec<u8>>,
     /// Unified debug events for IPC traffic in both directions.
     pub debug_ipc_events: VecDeque<DebugIpcEvent>,
+    /// Console, navigation, network, permission and popup events for the inspector timeline.
+    pub debug_timeline_events: VecDeque<DebugTimelineEvent>,
 
     /// URL change notifications.
     pub url_changes: VecDeque<String>,
@@ -396,6 +638,10 @@ pub struct EventQueues {
     pub title_changes: VecDeque<String>,
     /// Loading state events.
     pub loading_states: VecDeque<LoadingStateEvent>,
+    /// Resource load events, queued only while `enable_resource_events` is on.
+    pub resource_loads: VecDeque<ResourceLoadEvent>,
+    /// Requests canceled by the adblock engine.
+    pub blocked_requests: VecDeque<BlockedRequestEvent>,
     /// IME enable/disable requests.
     pub ime_enables: VecDeque<bool>,
     /// IME composition range (latest value wins).
@@ -412,10 +658,20 @@ pub struct EventQueues {
     pub download_updates: VecDeque<DownloadUpdateEvent>,
     /// Permission request events.
     pub permission_requests: VecDeque<PermissionRequestEvent>,
+    /// Certificate errors waiting for `allow_certificate` or `deny_certificate`.
+    pub certificate_errors: VecDeque<crate::certificate::CertificateErrorEvent>,
+    /// Servers and proxies waiting for `provide_credentials` or `cancel_auth`.
+    pub auth_requests: VecDeque<crate::auth::AuthRequestEvent>,
+    /// Requests waiting for the node's request interceptors.
+    pub intercepted_requests: VecDeque<InterceptedRequestEvent>,
+    /// Response bodies waiting for the node's response filters.
+    pub filtered_responses: VecDeque<FilteredResponseEvent>,
     /// Find-in-page result events.
     pub find_results: VecDeque<FindResultEvent>,
     /// Cookie operation results.
     pub cookie_events: VecDeque<CookieEvent>,
+    /// DevTools method results and events.
+    pub devtools_events: VecDeque<crate::devtools::DevToolsEvent>,
     /// Render process terminated event.
     pub render_process_terminated: VecDeque<(String, cef::TerminationStatus)>, // (reason, status)
 }
@@ -543,6 +799,16 @@ pub struct BrowserState {
     pub pending_permission_requests: PendingPermissionRequests,
     /// Shared per-callback aggregation state for multi-permission requests.
     pub pending_permission_aggregates: PendingPermissionAggregates,
+    /// Keeps the DevTools message observer registered once `execute_devtools_method` is used.
+    pub devtools_registration: Option<cef::Registration>,
+    /// Message ids of DevTools calls the node made itself, whose results are not emitted.
+    pub internal_devtools_results: std::collections::HashSet<i32>,
+    /// URL loaded once the result of the internal DevTools call with this id arrives.
+    pub navigation_after_devtools_result: Option<(i32, String)>,
+    /// Certificate error callbacks waiting for the node's decision.
+    pub pending_certificate_errors: PendingCertificateErrors,
+    /// Authentication callbacks waiting for the node's credentials.
+    pub pending_auth_requests: PendingAuthRequests,
 }
 
 /// CEF browser state and shared resources.
@@ -561,10 +827,23 @@ pub enum LifecycleState {
     Closed,
 }
 
+/// A browser CEF creates asynchronously, e.g. a DevTools frontend from `show_dev_tools`.
+/// It becomes `App::state` once `on_after_created` has handed it over.
+pub(crate) struct PendingBrowser {
+    pub created: crate::webrender::CreatedBrowserSlot,
+    pub requested_at: std::time::Instant,
+    /// Builds the runtime state around the created browser.
+    pub into_state: Box<dyn FnOnce(cef::Browser) -> BrowserState>,
+    /// Frees what was allocated for the browser when it is never adopted.
+    pub release: Box<dyn FnOnce()>,
+}
+
 #[derive(Default)]
 pub struct App {
     /// Active browser state, present when a browser instance is running.
     pub state: Option<BrowserState>,
+    /// Browser still being created by CEF, adopted into `state` in `on_process`.
+    pub(crate) pending_browser: Option<PendingBrowser>,
     /// Current drag state for this browser.
     pub drag_state: DragState,
     /// Tracks whether this instance currently holds one `cef_retain()` reference.
@@ -589,6 +868,14 @@ impl App {
         self.state.as_ref().and_then(|s| s.browser.host())
     }
 
+    /// Loads `url` in the main frame, if a browser is active.
+    pub fn load_url(&self, url: &str) {
+        use cef::ImplFrame;
+        if let Some(frame) = self.state.as_ref().and_then(|s| s.browser.main_frame()) {
+            frame.load_url(Some(&url.into()));
+        }
+    }
+
     pub fn lifecycle_state(&self) -> LifecycleState {
         self.lifecycle_state
     }
@@ -596,6 +883,7 @@ impl App {
     /// Clears per-instance runtime state. This is used during `CefTexture` cleanup
     /// and can be reused by tests as a deterministic reset point.
     pub fn clear_runtime_state(&mut self) {
+        self.abandon_pending_browser();
         self.state = None;
         self.drag_state = Default::default();
         if self.cef_retained {
@@ -605,6 +893,19 @@ impl App {
         }
     }
 
+    /// Whether CEF is still creating this instance's browser.
+    pub fn is_browser_pending(&self) -> bool {
+        self.pending_browser.is_some()
+    }
+
+    /// Gives up on a browser CEF is still creating; it is closed if it arrives later.
+    pub fn abandon_pending_browser(&mut self) {
+        if let Some(pending) = self.pending_browser.take() {
+            pending.created.abandon();
+            (pending.release)();
+        }
+    }
+
     /// Marks that this instance has successfully called `cef_retain()`.
     pub fn mark_cef_retained(&mut self) {
         self.cef_retained = true;
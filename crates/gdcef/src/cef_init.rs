@@ -60,6 +60,7 @@ pub fn cef_release() {
     state.ref_count -= 1;
 
     if state.ref_count == 0 && state.initialized {
+        crate::profiles::release_profile_contexts();
         cef::shutdown();
         state.initialized = false;
     }
@@ -138,6 +139,7 @@ fn initialize_cef() -> CefResult<()> {
     let proxy_bypass_list = settings::get_proxy_bypass_list();
     let cache_size_mb = settings::get_cache_size_mb();
     let custom_switches = settings::get_custom_switches();
+    let custom_schemes = settings::get_custom_schemes();
 
     godot::global::godot_print!(
         "[CefInit] Startup summary: backend={:?}, accelerated_osr_supported={}, reason={}, remote_debugging={}, remote_port={}, cache_size_mb={}",
@@ -159,7 +161,8 @@ fn initialize_cef() -> CefResult<()> {
         .proxy_server(proxy_server)
         .proxy_bypass_list(proxy_bypass_list)
         .cache_size_mb(cache_size_mb)
-        .custom_switches(custom_switches);
+        .custom_switches(custom_switches)
+        .custom_schemes(custom_schemes);
 
     #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
     {
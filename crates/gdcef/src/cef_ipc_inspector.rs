@@ -1,11 +1,13 @@
 use crate::utils::should_enable_ipc_inspector;
 use godot::builtin::{VarArray, VarDictionary, Variant, VariantType};
+use godot::classes::file_access::ModeFlags;
 use godot::classes::notify::ControlNotification;
 use godot::classes::{
-    Button, Control, IControl, Label, Node, OptionButton, PackedScene, PanelContainer,
-    ScrollContainer, VBoxContainer,
+    Button, Control, DirAccess, FileAccess, IControl, Json, Label, LineEdit, MenuButton, Node,
+    OptionButton, PackedScene, PanelContainer, ProjectSettings, ScrollContainer, Time, Tree,
+    TreeItem, VBoxContainer,
 };
-use godot::global::godot_warn;
+use godot::global::{Error, godot_warn};
 use godot::prelude::*;
 use std::collections::HashMap;
 
@@ -18,15 +20,56 @@ const DEFAULT_EXPANDED_MAX_CHARS: usize = 120;
 const DEFAULT_EXPANDED_MAX_LINES: usize = 3;
 const PREVIEW_MAX_CHARS: usize = 240;
 const PREVIEW_MAX_LINES: usize = 3;
+const EXPORT_DIR: &str = "user://cef_ipc_inspector";
+const PAYLOAD_TREE_MAX_ITEMS: usize = 500;
+const PAYLOAD_TREE_MAX_VISIBLE_ROWS: usize = 12;
+const PAYLOAD_TREE_ROW_HEIGHT: f32 = 24.0;
+const EXPORT_MENU_JSON: i64 = 0;
+const EXPORT_MENU_CBOR: i64 = 1;
+const COMPOSER_LANE_TEXT: i32 = 0;
+const COMPOSER_LANE_DATA: i32 = 1;
+const COMPOSER_LANE_BINARY: i32 = 2;
+const CATEGORY_IPC: &str = "ipc";
+/// Category filter entries as (label, category). An empty category matches everything.
+const CATEGORY_FILTERS: [(&str, &str); 7] = [
+    ("All events", ""),
+    ("IPC", CATEGORY_IPC),
+    ("Console", "console"),
+    ("Navigation", "navigation"),
+    ("Network", "network"),
+    ("Permission", "permission"),
+    ("Popup", "popup"),
+];
 
 #[derive(Clone, Debug)]
 struct InspectorMessage {
     id: i64,
+    /// `ipc` for IPC traffic, otherwise the timeline event kind (`console`, `network`, ...).
+    category: String,
     direction: String,
+    /// IPC lane, or the event name for timeline entries.
     lane: String,
     body: String,
     timestamp_unix_ms: i64,
     body_size_bytes: i64,
+    /// Decoded payload for the `data` lane, rendered as a collapsible tree.
+    data: Option<Variant>,
+}
+
+/// Export formats for the captured message log.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ExportFormat {
+    Json,
+    Cbor,
+}
+
+impl ExportFormat {
+    const fn extension(self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Cbor => "cbor",
+        }
+    }
 }
 
 #[derive(GodotClass)]
@@ -47,13 +90,24 @@ pub struct CefIpcInspector {
     panel: Option<Gd<PanelContainer>>,
     title_label: Option<Gd<Label>>,
     direction_filter: Option<Gd<OptionButton>>,
+    export_button: Option<Gd<MenuButton>>,
     clear_button: Option<Gd<Button>>,
+    search_edit: Option<Gd<LineEdit>>,
+    category_filter: Option<Gd<OptionButton>>,
+    lane_filter: Option<Gd<OptionButton>>,
     scroll: Option<Gd<ScrollContainer>>,
     message_list: Option<Gd<VBoxContainer>>,
     empty_label: Option<Gd<Label>>,
+    composer_lane: Option<Gd<OptionButton>>,
+    composer_input: Option<Gd<LineEdit>>,
+    send_button: Option<Gd<Button>>,
+    status_label: Option<Gd<Label>>,
 
     is_open: bool,
     selected_filter: i32,
+    selected_lane_filter: i32,
+    selected_category_filter: i32,
+    search_query: String,
     messages: Vec<InspectorMessage>,
     expanded_by_id: HashMap<i64, bool>,
     next_message_id: i64,
@@ -72,12 +126,23 @@ impl IControl for CefIpcInspector {
             panel: None,
             title_label: None,
             direction_filter: None,
+            export_button: None,
             clear_button: None,
+            search_edit: None,
+            category_filter: None,
+            lane_filter: None,
             scroll: None,
             message_list: None,
             empty_label: None,
+            composer_lane: None,
+            composer_input: None,
+            send_button: None,
+            status_label: None,
             is_open: false,
             selected_filter: 0,
+            selected_lane_filter: 0,
+            selected_category_filter: 0,
+            search_query: String::new(),
             messages: Vec::new(),
             expanded_by_id: HashMap::new(),
             next_message_id: 1,
@@ -132,6 +197,92 @@ impl CefIpcInspector {
         self.render_messages();
     }
 
+    #[func]
+    fn _on_lane_filter_changed(&mut self, idx: i32) {
+        self.selected_lane_filter = idx;
+        self.render_messages();
+    }
+
+    #[func]
+    fn _on_category_filter_changed(&mut self, idx: i32) {
+        self.selected_category_filter = idx;
+        self.render_messages();
+    }
+
+    #[func]
+    fn _on_search_changed(&mut self, text: GString) {
+        self.search_query = text.to_string().to_lowercase();
+        self.render_messages();
+    }
+
+    #[func]
+    fn _on_export_menu_id_pressed(&mut self, id: i64) {
+        let format = match id {
+            EXPORT_MENU_JSON => ExportFormat::Json,
+            EXPORT_MENU_CBOR => ExportFormat::Cbor,
+            _ => return,
+        };
+
+        if DirAccess::make_dir_recursive_absolute(EXPORT_DIR) != Error::OK {
+            self.report_error(&format!("Failed to create export directory {}", EXPORT_DIR));
+            return;
+        }
+
+        let unix_ms = (Time::singleton().get_unix_time_from_system() * 1000.0) as i64;
+        let path = format!("{}/ipc-log-{}.{}", EXPORT_DIR, unix_ms, format.extension());
+        if self.export_log(&path, format) {
+            let absolute = ProjectSettings::singleton().globalize_path(path.as_str());
+            self.set_status(&format!(
+                "Exported {} messages to {}",
+                self.messages.len(),
+                absolute
+            ));
+        }
+    }
+
+    #[func]
+    fn _on_send_pressed(&mut self) {
+        let Some(mut input) = self.composer_input.clone() else {
+            return;
+        };
+        let text = input.get_text().to_string();
+        if text.is_empty() {
+            return;
+        }
+
+        let lane = self
+            .composer_lane
+            .as_ref()
+            .map(|lane| lane.get_selected())
+            .unwrap_or(COMPOSER_LANE_TEXT);
+        match self.send_composed_message(lane, &text) {
+            Ok(()) => {
+                input.clear();
+                self.set_status("");
+            }
+            Err(err) => self.report_error(&err),
+        }
+    }
+
+    #[func]
+    fn _on_composer_submitted(&mut self, _text: GString) {
+        self._on_send_pressed();
+    }
+
+    /// Writes the captured message log to `path` as pretty-printed JSON.
+    /// Returns `true` on success.
+    #[func]
+    pub fn export_log_json(&mut self, path: GString) -> bool {
+        self.export_log(&path.to_string(), ExportFormat::Json)
+    }
+
+    /// Writes the captured message log to `path` as a CBOR array.
+    /// Returns `true` on success.
+    #[func]
+    pub fn export_log_cbor(&mut self, path: GString) -> bool {
+        self.export_log(&path.to_string(), ExportFormat::Cbor)
+    }
+
     #[func]
     fn _on_child_entered_tree(&mut self, _node: Gd<Node>) {
         if self.cef_texture.is_none() {
@@ -148,12 +299,45 @@ impl CefIpcInspector {
         let raw = event.to::<VarDictionary>();
         let entry = InspectorMessage {
             id: self.next_message_id,
+            category: CATEGORY_IPC.to_string(),
             direction: Self::dict_get_string(&raw, "direction"),
             lane: Self::dict_get_string(&raw, "lane"),
             body: Self::dict_get_string(&raw, "body"),
             timestamp_unix_ms: Self::dict_get_i64(&raw, "timestamp_unix_ms"),
             body_size_bytes: Self::dict_get_i64(&raw, "body_size_bytes"),
+            data: raw.get("data").filter(|data| !data.is_nil()),
+        };
+        self.push_message(entry);
+    }
+
+    #[func]
+    fn _on_debug_timeline_event(&mut self, event: Variant) {
+        if event.get_type() != VariantType::DICTIONARY {
+            return;
+        }
+
+        let raw = event.to::<VarDictionary>();
+        let url = Self::dict_get_string(&raw, "url");
+        let detail = Self::dict_get_string(&raw, "detail");
+        let body = match (detail.is_empty(), url.is_empty()) {
+            (true, _) => url,
+            (false, true) => detail,
+            (false, false) => format!("{}\n{}", detail, url),
         };
+        let entry = InspectorMessage {
+            id: self.next_message_id,
+            category: Self::dict_get_string(&raw, "kind"),
+            direction: String::new(),
+            lane: Self::dict_get_string(&raw, "name"),
+            body_size_bytes: saturating_len(&body),
+            body,
+            timestamp_unix_ms: Self::dict_get_i64(&raw, "timestamp_unix_ms"),
+            data: None,
+        };
+        self.push_message(entry);
+    }
+
+    fn push_message(&mut self, entry: InspectorMessage) {
         self.next_message_id += 1;
 
         self.messages.push(entry);
@@ -241,12 +425,27 @@ impl CefIpcInspector {
             content_root.try_get_node_as("InspectorPanel/Margin/VBox/Header/TitleLabel");
         self.direction_filter =
             content_root.try_get_node_as("InspectorPanel/Margin/VBox/Header/DirectionFilter");
+        self.export_button =
+            content_root.try_get_node_as("InspectorPanel/Margin/VBox/Header/ExportButton");
         self.clear_button =
             content_root.try_get_node_as("InspectorPanel/Margin/VBox/Header/ClearButton");
+        self.search_edit =
+            content_root.try_get_node_as("InspectorPanel/Margin/VBox/Toolbar/SearchEdit");
+        self.category_filter =
+            content_root.try_get_node_as("InspectorPanel/Margin/VBox/Toolbar/CategoryFilter");
+        self.lane_filter =
+            content_root.try_get_node_as("InspectorPanel/Margin/VBox/Toolbar/LaneFilter");
         self.scroll = content_root.try_get_node_as("InspectorPanel/Margin/VBox/Scroll");
         self.message_list =
             content_root.try_get_node_as("InspectorPanel/Margin/VBox/Scroll/MessageList");
         self.empty_label = content_root.try_get_node_as("InspectorPanel/Margin/VBox/EmptyLabel");
+        self.composer_lane =
+            content_root.try_get_node_as("InspectorPanel/Margin/VBox/Composer/ComposerLane");
+        self.composer_input =
+            content_root.try_get_node_as("InspectorPanel/Margin/VBox/Composer/ComposerInput");
+        self.send_button =
+            content_root.try_get_node_as("InspectorPanel/Margin/VBox/Composer/SendButton");
+        self.status_label = content_root.try_get_node_as("InspectorPanel/Margin/VBox/StatusLabel");
 
         self.ui_root = Some(ui_root);
 
@@ -285,6 +484,52 @@ impl CefIpcInspector {
             }
         }
 
+        if let Some(mut filter) = self.category_filter.clone() {
+            let callable = self.base().callable("_on_category_filter_changed");
+            if !filter.is_connected("item_selected", &callable) {
+                filter.connect("item_selected", &callable);
+            }
+        }
+
+        if let Some(mut filter) = self.lane_filter.clone() {
+            let callable = self.base().callable("_on_lane_filter_changed");
+            if !filter.is_connected("item_selected", &callable) {
+                filter.connect("item_selected", &callable);
+            }
+        }
+
+        if let Some(mut search) = self.search_edit.clone() {
+            let callable = self.base().callable("_on_search_changed");
+            if !search.is_connected("text_changed", &callable) {
+                search.connect("text_changed", &callable);
+            }
+        }
+
+        if let Some(mut popup) = self
+            .export_button
+            .as_ref()
+            .and_then(|export| export.get_popup())
+        {
+            let callable = self.base().callable("_on_export_menu_id_pressed");
+            if !popup.is_connected("id_pressed", &callable) {
+                popup.connect("id_pressed", &callable);
+            }
+        }
+
+        if let Some(mut send) = self.send_button.clone() {
+            let callable = self.base().callable("_on_send_pressed");
+            if !send.is_connected("pressed", &callable) {
+                send.connect("pressed", &callable);
+            }
+        }
+
+        if let Some(mut input) = self.composer_input.clone() {
+            let callable = self.base().callable("_on_composer_submitted");
+            if !input.is_connected("text_submitted", &callable) {
+                input.connect("text_submitted", &callable);
+            }
+        }
+
         let callable = self.base().callable("_on_child_entered_tree");
         let mut base = self.base_mut();
         if !base.is_connected("child_entered_tree", &callable) {
@@ -301,6 +546,55 @@ impl CefIpcInspector {
             filter.select(0);
             self.selected_filter = 0;
         }
+
+        if let Some(mut filter) = self.category_filter.clone() {
+            filter.clear();
+            for (label, _) in CATEGORY_FILTERS {
+                filter.add_item(label);
+            }
+            filter.select(0);
+            self.selected_category_filter = 0;
+        }
+
+        if let Some(mut filter) = self.lane_filter.clone() {
+            filter.clear();
+            filter.add_item("All lanes");
+            filter.add_item("Text");
+            filter.add_item("Binary");
+            filter.add_item("Data");
+            filter.select(0);
+            self.selected_lane_filter = 0;
+        }
+
+        if let Some(mut popup) = self
+            .export_button
+            .as_ref()
+            .and_then(|export| export.get_popup())
+        {
+            popup.clear();
+            popup.add_item("Export JSON");
+            popup.add_item("Export CBOR");
+        }
+
+        if let Some(mut lane) = self.composer_lane.clone() {
+            lane.clear();
+            lane.add_item("Text");
+            lane.add_item("Data (JSON)");
+            lane.add_item("Binary (hex)");
+            lane.select(COMPOSER_LANE_TEXT);
+        }
+    }
+
+    fn report_error(&mut self, text: &str) {
+        godot_warn!("[CefIpcInspector] {}", text);
+        self.set_status(text);
+    }
+
+    fn set_status(&mut self, text: &str) {
+        if let Some(mut status) = self.status_label.clone() {
+            status.set_visible(!text.is_empty());
+            status.set_text(text);
+        }
     }
 
     fn update_ui_state(&mut self) {
@@ -348,6 +642,10 @@ impl CefIpcInspector {
         if !target.is_connected("debug_ipc_message", &debug_callable) {
             target.connect("debug_ipc_message", &debug_callable);
         }
+        let timeline_callable = self.base().callable("_on_debug_timeline_event");
+        if !target.is_connected("debug_timeline_event", &timeline_callable) {
+            target.connect("debug_timeline_event", &timeline_callable);
+        }
         self.cef_texture = Some(target);
 
         if let Some(mut empty) = self.empty_label.clone()
@@ -388,11 +686,127 @@ impl CefIpcInspector {
     }
 
     fn passes_filter(&self, msg: &InspectorMessage) -> bool {
-        match self.selected_filter {
+        let category = CATEGORY_FILTERS
+            .get(self.selected_category_filter.max(0) as usize)
+            .map(|(_, category)| *category)
+            .unwrap_or_default();
+        if !category.is_empty() && msg.category != category {
+            return false;
+        }
+
+        // Direction and lane filters only describe IPC traffic, so an active
+        // filter hides timeline entries.
+        if msg.category != CATEGORY_IPC {
+            return self.selected_filter == 0
+                && self.selected_lane_filter == 0
+                && Self::matches_search(msg, &self.search_query);
+        }
+
+        let direction_ok = match self.selected_filter {
             1 => msg.direction == "to_renderer",
             2 => msg.direction == "to_godot",
             _ => true,
+        };
+        let lane_ok = match self.selected_lane_filter {
+            1 => msg.lane == "text",
+            2 => msg.lane == "binary",
+            3 => msg.lane == "data",
+            _ => true,
+        };
+        direction_ok && lane_ok && Self::matches_search(msg, &self.search_query)
+    }
+
+    /// Case-insensitive match of `query` (already lowercased) against the
+    /// payload preview, category, lane or event name, and direction label.
+    fn matches_search(msg: &InspectorMessage, query: &str) -> bool {
+        if query.is_empty() {
+            return true;
         }
+        msg.body.to_lowercase().contains(query)
+            || msg.category.contains(query)
+            || msg.lane.to_lowercase().contains(query)
+            || Self::direction_label(&msg.direction)
+                .to_lowercase()
+                .contains(query)
+    }
+
+    fn send_composed_message(&mut self, lane: i32, text: &str) -> Result<(), String> {
+        let Some(mut target) = self.target_cef_texture.clone() else {
+            return Err("Assign target_cef_texture before sending messages.".to_string());
+        };
+
+        match lane {
+            COMPOSER_LANE_DATA => {
+                let mut json = Json::new_gd();
+                if json.parse(text) != Error::OK {
+                    return Err(format!(
+                        "Invalid JSON at line {}: {}",
+                        json.get_error_line(),
+                        json.get_error_message()
+                    ));
+                }
+                target.bind_mut().send_ipc_data(json.get_data());
+            }
+            COMPOSER_LANE_BINARY => {
+                let bytes = parse_hex_bytes(text)?;
+                target
+                    .bind_mut()
+                    .send_ipc_binary_message(PackedByteArray::from(bytes.as_slice()));
+            }
+            _ => target.bind_mut().send_ipc_message(text.into()),
+        }
+        Ok(())
+    }
+
+    fn export_log(&mut self, path: &str, format: ExportFormat) -> bool {
+        let mut entries = VarArray::new();
+        for msg in &self.messages {
+            let mut entry = VarDictionary::new();
+            entry.set("category", GString::from(&msg.category).to_variant());
+            if msg.category == CATEGORY_IPC {
+                entry.set("direction", GString::from(&msg.direction).to_variant());
+                entry.set("lane", GString::from(&msg.lane).to_variant());
+            } else {
+                entry.set("name", GString::from(&msg.lane).to_variant());
+            }
+            entry.set("body", GString::from(&msg.body).to_variant());
+            entry.set("timestamp_unix_ms", msg.timestamp_unix_ms.to_variant());
+            entry.set("body_size_bytes", msg.body_size_bytes.to_variant());
+            if let Some(data) = msg.data.as_ref() {
+                entry.set("data", data.clone());
+            }
+            entries.push(&entry.to_variant());
+        }
+        let entries = entries.to_variant();
+
+        let Some(mut file) = FileAccess::open(path, ModeFlags::WRITE) else {
+            self.report_error(&format!(
+                "Failed to open {} for writing: {:?}",
+                path,
+                FileAccess::get_open_error()
+            ));
+            return false;
+        };
+
+        let written = match format {
+            ExportFormat::Json => {
+                let text = Json::stringify_ex(&entries).indent("  ").done();
+                file.store_string(&text)
+            }
+            ExportFormat::Cbor => match crate::ipc_data::encode_variant_to_cbor_bytes(&entries) {
+                Ok(bytes) => file.store_buffer(&PackedByteArray::from(bytes.as_slice())),
+                Err(err) => {
+                    self.report_error(&format!("Failed to encode log as CBOR: {}", err));
+                    return false;
+                }
+            },
+        };
+        file.close();
+
+        if !written {
+            self.report_error(&format!("Failed to write IPC log to {}", path));
+        }
+        written
     }
 
     fn build_message_card(&mut self, msg: &InspectorMessage) -> Gd<PanelContainer> {
@@ -415,6 +829,7 @@ impl CefIpcInspector {
             header.set_text(&header_text);
         }
 
+        let show_tree = expanded && msg.data.is_some();
         let body_text = if expanded {
             msg.body.clone()
         } else {
@@ -422,10 +837,18 @@ impl CefIpcInspector {
         };
         if let Some(mut body) = card.try_get_node_as::<Label>("Margin/VBox/BodyLabel") {
             body.set_text(&body_text);
+            body.set_visible(!show_tree);
+        }
+
+        if let Some(mut tree) = card.try_get_node_as::<Tree>("Margin/VBox/PayloadTree") {
+            tree.set_visible(show_tree);
+            if show_tree && let Some(data) = msg.data.as_ref() {
+                Self::populate_payload_tree(&mut tree, data);
+            }
         }
 
         if let Some(mut toggle) = card.try_get_node_as::<Button>("Margin/VBox/ToggleButton") {
-            if default_expanded {
+            if default_expanded && msg.data.is_none() {
                 toggle.set_visible(false);
             } else {
                 toggle.set_visible(true);
@@ -441,7 +864,86 @@ impl CefIpcInspector {
         card
     }
 
+    fn populate_payload_tree(tree: &mut Gd<Tree>, data: &Variant) {
+        tree.clear();
+        tree.set_columns(1);
+        let Some(mut root) = tree.create_item() else {
+            return;
+        };
+
+        let mut budget = PAYLOAD_TREE_MAX_ITEMS;
+        let mut rows = 0usize;
+        Self::append_tree_value(&mut root, "payload", data, &mut budget, &mut rows);
+
+        let visible_rows = rows.clamp(1, PAYLOAD_TREE_MAX_VISIBLE_ROWS) as f32;
+        tree.set_custom_minimum_size(Vector2::new(
+            0.0,
+            visible_rows * PAYLOAD_TREE_ROW_HEIGHT + 8.0,
+        ));
+        tree.set_v_scroll_enabled(rows > PAYLOAD_TREE_MAX_VISIBLE_ROWS);
+    }
+
+    fn append_tree_value(
+        parent: &mut Gd<TreeItem>,
+        key: &str,
+        value: &Variant,
+        budget: &mut usize,
+        rows: &mut usize,
+    ) {
+        if *budget == 0 {
+            return;
+        }
+        let Some(mut item) = parent.create_child() else {
+            return;
+        };
+        *budget -= 1;
+        *rows += 1;
+
+        match value.get_type() {
+            VariantType::DICTIONARY => {
+                let dict = value.to::<VarDictionary>();
+                item.set_text(0, &format!("{}: {{…}} ({} keys)", key, dict.len()));
+                for (child_key, child_value) in dict.iter_shared() {
+                    let child_key = child_key.stringify().to_string();
+                    Self::append_tree_value(&mut item, &child_key, &child_value, budget, rows);
+                }
+            }
+            VariantType::ARRAY => {
+                let array = value.to::<VarArray>();
+                item.set_text(0, &format!("{}: […] ({} items)", key, array.len()));
+                for (index, child_value) in array.iter_shared().enumerate() {
+                    Self::append_tree_value(
+                        &mut item,
+                        &index.to_string(),
+                        &child_value,
+                        budget,
+                        rows,
+                    );
+                }
+            }
+            VariantType::PACKED_BYTE_ARRAY => {
+                let bytes = value.to::<PackedByteArray>();
+                item.set_text(0, &format!("{}: <{} bytes>", key, bytes.len()));
+            }
+            VariantType::STRING => {
+                item.set_text(0, &format!("{}: \"{}\"", key, value.stringify()));
+            }
+            _ => {
+                item.set_text(0, &format!("{}: {}", key, value.stringify()));
+            }
+        }
+    }
+
     fn format_header(&self, msg: &InspectorMessage) -> String {
+        if msg.category != CATEGORY_IPC {
+            return format!(
+                "{}  |  {}  |  {}",
+                Self::format_timestamp_ms(msg.timestamp_unix_ms),
+                msg.category.to_uppercase(),
+                msg.lane
+            );
+        }
+
         format!(
             "{}  |  {}  |  {}  |  {} B",
             Self::format_timestamp_ms(msg.timestamp_unix_ms),
@@ -508,3 +1010,95 @@ impl CefIpcInspector {
         }
     }
 }
+
+fn saturating_len(text: &str) -> i64 {
+    text.len().try_into().unwrap_or(i64::MAX)
+}
+
+/// Parses a hex string such as `DE AD be ef`, `0xde,0xad` or `deadbeef` into bytes.
+fn parse_hex_bytes(input: &str) -> Result<Vec<u8>, String> {
+    let mut digits = String::with_capacity(input.len());
+    for token in input.split(|c: char| c.is_whitespace() || c == ',') {
+        let token = token
+            .strip_prefix("0x")
+            .or_else(|| token.strip_prefix("0X"))
+            .unwrap_or(token);
+        digits.push_str(token);
+    }
+
+    if digits.is_empty() {
+        return Err("Binary payload is empty".to_string());
+    }
+    if !digits.len().is_multiple_of(2) {
+        return Err("Hex payload must contain an even number of digits".to_string());
+    }
+
+    digits
+        .as_bytes()
+        .chunks(2)
+        .map(|pair| {
+            std::str::from_utf8(pair)
+                .ok()
+                .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+                .ok_or_else(|| format!("Invalid hex byte: {}", String::from_utf8_lossy(pair)))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(direction: &str, lane: &str, body: &str) -> InspectorMessage {
+        InspectorMessage {
+            id: 1,
+            category: CATEGORY_IPC.to_string(),
+            direction: direction.to_string(),
+            lane: lane.to_string(),
+            body: body.to_string(),
+            timestamp_unix_ms: 0,
+            body_size_bytes: body.len() as i64,
+            data: None,
+        }
+    }
+
+    #[test]
+    fn parse_hex_bytes_accepts_common_formats() {
+        assert_eq!(
+            parse_hex_bytes("deadBEEF"),
+            Ok(vec![0xDE, 0xAD, 0xBE, 0xEF])
+        );
+        assert_eq!(
+            parse_hex_bytes("DE AD be ef"),
+            Ok(vec![0xDE, 0xAD, 0xBE, 0xEF])
+        );
+        assert_eq!(parse_hex_bytes("0xde, 0xAD"), Ok(vec![0xDE, 0xAD]));
+    }
+
+    #[test]
+    fn parse_hex_bytes_rejects_invalid_input() {
+        assert!(parse_hex_bytes("").is_err());
+        assert!(parse_hex_bytes("abc").is_err());
+        assert!(parse_hex_bytes("zz").is_err());
+    }
+
+    #[test]
+    fn search_matches_body_lane_and_direction() {
+        let msg = message("to_godot", "data", "{\"Score\": 10}");
+        assert!(CefIpcInspector::matches_search(&msg, ""));
+        assert!(CefIpcInspector::matches_search(&msg, "score"));
+        assert!(CefIpcInspector::matches_search(&msg, "data"));
+        assert!(CefIpcInspector::matches_search(&msg, "outgoing"));
+        assert!(!CefIpcInspector::matches_search(&msg, "incoming"));
+    }
+
+    #[test]
+    fn search_matches_timeline_category_and_name() {
+        let mut msg = message("", "load_error", "-105 ERR_NAME_NOT_RESOLVED");
+        msg.category = "navigation".to_string();
+        assert!(CefIpcInspector::matches_search(&msg, "navigation"));
+        assert!(CefIpcInspector::matches_search(&msg, "load_error"));
+        assert!(CefIpcInspector::matches_search(&msg, "err_name"));
+        assert!(!CefIpcInspector::matches_search(&msg, "console"));
+    }
+}
@@ -0,0 +1,52 @@
+use godot::global::godot_warn;
+use godot::prelude::*;
+
+use crate::{godot_protocol, settings};
+
+/// Binds GDScript callables to the custom schemes declared in
+/// `godot_cef/schemes/custom_schemes`.
+///
+/// A handler is called as `handler(method, url, headers, body)` and returns
+/// `{ "status": int, "headers": Dictionary, "body": PackedByteArray }`,
+/// either directly or after `await`.
+#[derive(GodotClass)]
+#[class(no_init)]
+pub struct CefSchemeRegistry {
+    base: Base<RefCounted>,
+}
+
+#[godot_api]
+impl CefSchemeRegistry {
+    /// Registers `handler` for `scheme`, replacing any previous handler.
+    /// Returns `false` if the scheme was not declared in project settings.
+    #[func]
+    pub fn register_handler(scheme: GString, handler: Callable) -> bool {
+        let scheme = godot_protocol::normalize_scheme_name(&scheme.to_string());
+        if !settings::get_custom_schemes().contains(&scheme) {
+            godot_warn!(
+                "[CefSchemeRegistry] Scheme '{}' is not listed in godot_cef/schemes/custom_schemes; \
+                 custom schemes must be declared before CEF starts.",
+                scheme
+            );
+            return false;
+        }
+
+        godot_protocol::register_custom_scheme_handler(&scheme, handler);
+        true
+    }
+
+    /// Removes the handler for `scheme`. Requests then receive a 404 response.
+    #[func]
+    pub fn unregister_handler(scheme: GString) -> bool {
+        godot_protocol::unregister_custom_scheme_handler(&scheme.to_string())
+    }
+
+    /// Returns the custom schemes declared in project settings.
+    #[func]
+    pub fn get_declared_schemes() -> PackedStringArray {
+        settings::get_custom_schemes()
+            .iter()
+            .map(|scheme| GString::from(scheme.as_str()))
+            .collect()
+    }
+}
@@ -0,0 +1,33 @@
+use crate::browser::App;
+
+/// Answers the request behind an `auth_requested` signal. `None` cancels it.
+pub(crate) fn resolve_auth_request(
+    app: &App,
+    request_id: i64,
+    credentials: Option<(&str, &str)>,
+) -> bool {
+    let Some(state) = app.state.as_ref() else {
+        godot::global::godot_warn!(
+            "[CefTexture] Cannot resolve auth request {}: no active browser",
+            request_id
+        );
+        return false;
+    };
+    let Some(callback) = state.pending_auth_requests.take(request_id) else {
+        godot::global::godot_warn!(
+            "[CefTexture] Unknown or stale auth request id: {}",
+            request_id
+        );
+        return false;
+    };
+
+    use cef::ImplAuthCallback;
+    match credentials {
+        Some((username, password)) => callback.cont(
+            Some(&cef::CefString::from(username)),
+            Some(&cef::CefString::from(password)),
+        ),
+        None => callback.cancel(),
+    }
+    true
+}
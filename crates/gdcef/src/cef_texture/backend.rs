@@ -1,5 +1,4 @@
-use adblock::lists::{FilterSet, ParseOptions};
-use cef::{BrowserSettings, ImplBrowser, ImplBrowserHost, RequestContextSettings, WindowInfo};
+use cef::{BrowserSettings, ImplBrowser, ImplBrowserHost, WindowInfo};
 use cef_app::PhysicalSize;
 use godot::classes::Image;
 use godot::classes::image::Format as ImageFormat;
@@ -7,18 +6,19 @@ use godot::classes::{AudioServer, DisplayServer, Engine, ImageTexture, Texture2D
 use godot::prelude::*;
 use software_render::{DestBuffer, PopupBuffer, composite_popup};
 use std::collections::HashMap;
-use std::fs;
-use std::rc::Rc;
 use std::sync::atomic::{AtomicI32, AtomicI64, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use crate::accelerated_osr::{
     self, AcceleratedRenderState, GodotTextureImporter, PlatformAcceleratedRenderHandler,
 };
-use crate::browser::{App, BrowserState, PopupPolicyFlag, PopupStateQueue, RenderMode};
+use crate::browser::{
+    App, BrowserState, PendingBrowser, PopupPolicyFlag, PopupStateQueue, RenderMode,
+};
 use crate::error::CefError;
 use crate::utils::get_display_scale_factor;
-use crate::{godot_protocol, render, webrender};
+use crate::{render, webrender};
 
 /// Shared browser creation inputs used by both `CefTexture` and `CefTexture2D`.
 pub(crate) struct BackendCreateParams {
@@ -26,9 +26,18 @@ pub(crate) struct BackendCreateParams {
     pub dpi: f32,
     pub max_fps: i32,
     pub url: String,
+    /// Profile name; empty uses an ephemeral request context.
+    pub profile: String,
+    /// Set on the request context before the browser is created; empty keeps the current value.
+    pub proxy: String,
+    /// Set on the request context like `proxy`.
+    pub accept_language: String,
     pub enable_accelerated_osr: bool,
     pub background_color: Color,
     pub popup_policy: i32,
+    pub request_hooks: crate::browser::RequestHooks,
+    /// Browser whose DevTools frontend this node shows instead of `url`.
+    pub devtools_for: Option<cef::BrowserHost>,
     pub software_target_texture: Option<Gd<ImageTexture>>,
     pub log_prefix: &'static str,
 }
@@ -43,6 +52,9 @@ struct BrowserCreateParams {
     permission_request_counter: crate::browser::PermissionRequestIdCounter,
     pending_permission_requests: crate::browser::PendingPermissionRequests,
     pending_permission_aggregates: crate::browser::PendingPermissionAggregates,
+    request_hooks: crate::browser::RequestHooks,
+    adblock_engine: Option<crate::adblock_engine::AdblockEngineHandle>,
+    devtools_for: Option<cef::BrowserHost>,
 }
 
 fn color_to_cef_color(color: Color) -> u32 {
@@ -50,38 +62,6 @@ fn color_to_cef_color(color: Color) -> u32 {
     u32::from_be_bytes([f(color.a), f(color.r), f(color.g), f(color.b)])
 }
 
-fn build_adblock_engine(log_prefix: &str) -> Option<Rc<adblock::Engine>> {
-    if !crate::settings::is_adblock_enabled() {
-        return None;
-    }
-
-    let Some(rules_path) = crate::settings::get_adblock_rules_path() else {
-        godot::global::godot_warn!(
-            "[{}] Adblock is enabled, but adblock rules path setting is empty. Request filtering will be disabled.",
-            log_prefix
-        );
-        return None;
-    };
-
-    let rules = match fs::read_to_string(&rules_path) {
-        Ok(content) => content,
-        Err(error) => {
-            godot::global::godot_warn!(
-                "[{}] Failed to read adblock rules file '{}': {}. Request filtering will be disabled.",
-                log_prefix,
-                rules_path.display().to_string(),
-                error
-            );
-            return None;
-        }
-    };
-
-    let mut filter_set = FilterSet::new(true);
-    let _metadata = filter_set.add_filter_list(&rules, ParseOptions::default());
-    godot::global::godot_print!("[{}] Adblock filter list loaded.", log_prefix);
-    Some(Rc::new(adblock::Engine::from_filter_set(filter_set, true)))
-}
-
 pub(crate) fn should_use_accelerated_osr(enable_accelerated_osr: bool, log_prefix: &str) -> bool {
     if !enable_accelerated_osr {
         godot::global::godot_print!(
@@ -374,16 +354,18 @@ pub(crate) fn try_create_browser(
         ..Default::default()
     };
 
-    let adblock_engine = build_adblock_engine(params.log_prefix);
-    let mut context = cef::request_context_create_context(
-        Some(&RequestContextSettings::default()),
-        Some(&mut webrender::RequestContextHandlerImpl::build(
-            webrender::OsrRequestContextHandler::new(adblock_engine),
-        )),
+    let adblock_engine = crate::adblock_engine::shared_engine(params.log_prefix);
+    let mut context = crate::profiles::request_context(
+        &params.profile,
+        adblock_engine.clone(),
+        params.log_prefix,
     );
-    if let Some(ctx) = context.as_mut() {
-        godot_protocol::register_res_scheme_handler_on_context(ctx);
-        godot_protocol::register_user_scheme_handler_on_context(ctx);
+    if let Some(context) = context.as_ref() {
+        super::network_ops::apply_context_overrides(
+            context,
+            &params.proxy,
+            &params.accept_language,
+        );
     }
 
     let create_params = BrowserCreateParams {
@@ -395,6 +377,9 @@ pub(crate) fn try_create_browser(
         permission_request_counter,
         pending_permission_requests,
         pending_permission_aggregates,
+        request_hooks: params.request_hooks.clone(),
+        adblock_engine,
+        devtools_for: params.devtools_for.clone(),
     };
 
     if use_accelerated {
@@ -423,9 +408,16 @@ pub(crate) fn try_create_browser(
 }
 
 pub(crate) fn cleanup_runtime(app: &mut App, popup_texture_2d_rd: Option<&mut Gd<Texture2Drd>>) {
+    close_runtime(app, popup_texture_2d_rd);
+    app.release_cef_if_retained();
+}
+
+/// Closes the browser and frees its resources but keeps CEF retained, so the node
+/// can create a new browser right away.
+pub(crate) fn close_runtime(app: &mut App, popup_texture_2d_rd: Option<&mut Gd<Texture2Drd>>) {
     if app.state.is_none() {
+        app.abandon_pending_browser();
         app.mark_browser_closed();
-        app.release_cef_if_retained();
         return;
     }
     app.mark_browser_closing();
@@ -440,6 +432,10 @@ pub(crate) fn cleanup_runtime(app: &mut App, popup_texture_2d_rd: Option<&mut Gd
     {
         pending.clear();
     }
+    if let Some(state) = &app.state {
+        state.pending_certificate_errors.clear();
+        state.pending_auth_requests.clear();
+    }
 
     if let Some(state) = &app.state
         && let Some(audio) = &state.audio
@@ -474,7 +470,86 @@ pub(crate) fn cleanup_runtime(app: &mut App, popup_texture_2d_rd: Option<&mut Gd
 
     app.clear_runtime_state();
     app.mark_browser_closed();
-    app.release_cef_if_retained();
+}
+
+/// How long to wait for CEF to create a DevTools browser.
+const DEVTOOLS_CREATE_TIMEOUT: Duration = Duration::from_secs(5);
+
+enum LaunchedBrowser {
+    Created(cef::Browser),
+    /// CEF creates the browser later and hands it over through the slot.
+    Pending(webrender::CreatedBrowserSlot),
+}
+
+/// Creates the browser for `client`. With `devtools_for`, the browser shows that browser's
+/// DevTools frontend; CEF creates it asynchronously, so it is returned as pending.
+fn launch_browser(
+    window_info: &WindowInfo,
+    client: &mut cef::Client,
+    browser_settings: &BrowserSettings,
+    context: Option<&mut cef::RequestContext>,
+    devtools_for: Option<&cef::BrowserHost>,
+    created_browser: &webrender::CreatedBrowserSlot,
+    url: &str,
+) -> Option<LaunchedBrowser> {
+    let Some(inspected) = devtools_for else {
+        return cef::browser_host_create_browser_sync(
+            Some(window_info),
+            Some(client),
+            Some(&url.into()),
+            Some(browser_settings),
+            None,
+            context,
+        )
+        .map(LaunchedBrowser::Created);
+    };
+
+    created_browser.arm();
+    inspected.show_dev_tools(
+        Some(window_info),
+        Some(client),
+        Some(browser_settings),
+        None,
+    );
+    Some(LaunchedBrowser::Pending(created_browser.clone()))
+}
+
+/// Stores a launched browser in `app`, or keeps it pending until `adopt_pending_browser`.
+fn install_browser(
+    app: &mut App,
+    launched: LaunchedBrowser,
+    into_state: impl FnOnce(cef::Browser) -> BrowserState + 'static,
+    release: impl FnOnce() + 'static,
+) {
+    match launched {
+        LaunchedBrowser::Created(browser) => app.state = Some(into_state(browser)),
+        LaunchedBrowser::Pending(created) => {
+            app.pending_browser = Some(PendingBrowser {
+                created,
+                requested_at: Instant::now(),
+                into_state: Box::new(into_state),
+                release: Box::new(release),
+            });
+        }
+    }
+}
+
+/// Moves a pending browser into `app.state` once CEF has created it. Returns `None`
+/// while still waiting or when nothing is pending.
+pub(crate) fn adopt_pending_browser(app: &mut App) -> Option<Result<(), CefError>> {
+    let pending = app.pending_browser.as_ref()?;
+    if let Some(browser) = pending.created.take() {
+        let pending = app.pending_browser.take()?;
+        app.state = Some((pending.into_state)(browser));
+        return Some(Ok(()));
+    }
+    if pending.requested_at.elapsed() < DEVTOOLS_CREATE_TIMEOUT {
+        return None;
+    }
+    app.abandon_pending_browser();
+    Some(Err(CefError::BrowserCreationFailed(
+        "timed out waiting for the DevTools browser to be created".into(),
+    )))
 }
 
 fn create_software_browser(
@@ -495,6 +570,9 @@ fn create_software_browser(
         permission_request_counter,
         pending_permission_requests,
         pending_permission_aggregates,
+        request_hooks,
+        adblock_engine,
+        devtools_for,
     } = params;
     godot::global::godot_print!(
         "[{}] Creating browser in software rendering mode",
@@ -531,6 +609,7 @@ fn create_software_browser(
         permission_request_counter.clone(),
         pending_permission_requests.clone(),
         pending_permission_aggregates.clone(),
+        request_hooks,
     );
 
     let mut texture = software_target_texture.unwrap_or_else(ImageTexture::new_gd);
@@ -555,22 +634,26 @@ fn create_software_browser(
         cursor_type.clone(),
         queues.clone(),
         popup_policy.clone(),
+        adblock_engine,
     );
 
-    let browser = cef::browser_host_create_browser_sync(
-        Some(&window_info),
-        Some(&mut client),
-        Some(&url.into()),
-        Some(browser_settings),
-        None,
+    let launched = launch_browser(
+        &window_info,
+        &mut client,
+        browser_settings,
         context,
+        devtools_for.as_ref(),
+        &queues.created_browser,
+        url,
     )
     .ok_or_else(|| {
         CefError::BrowserCreationFailed("browser_host_create_browser_sync returned None".into())
     })?;
 
     let event_queues = queues.event_queues.clone();
-    app.state = Some(BrowserState {
+    let pending_certificate_errors = queues.pending_certificate_errors.clone();
+    let pending_auth_requests = queues.pending_auth_requests.clone();
+    let into_state = move |browser| BrowserState {
         browser,
         render_mode: RenderMode::Software {
             frame_buffer,
@@ -585,7 +668,13 @@ fn create_software_browser(
         popup_policy,
         pending_permission_requests,
         pending_permission_aggregates,
-    });
+        devtools_registration: None,
+        internal_devtools_results: Default::default(),
+        navigation_after_devtools_result: None,
+        pending_certificate_errors,
+        pending_auth_requests,
+    };
+    install_browser(app, launched, into_state, || {});
 
     Ok(())
 }
@@ -631,6 +720,9 @@ fn create_accelerated_browser(
         permission_request_counter,
         pending_permission_requests,
         pending_permission_aggregates,
+        request_hooks,
+        adblock_engine,
+        devtools_for,
     } = params;
 
     let (rd_texture_rid, texture_2d_rd) = render::create_rd_texture(pixel_width, pixel_height)?;
@@ -660,6 +752,7 @@ fn create_accelerated_browser(
         permission_request_counter.clone(),
         pending_permission_requests.clone(),
         pending_permission_aggregates.clone(),
+        request_hooks,
     );
 
     let cef_render_handler =
@@ -669,17 +762,19 @@ fn create_accelerated_browser(
         cursor_type.clone(),
         queues.clone(),
         popup_policy.clone(),
+        adblock_engine,
     );
 
-    let browser = match cef::browser_host_create_browser_sync(
-        Some(window_info),
-        Some(&mut client),
-        Some(&url.into()),
-        Some(browser_settings),
-        None,
+    let launched = match launch_browser(
+        window_info,
+        &mut client,
+        browser_settings,
         context,
+        devtools_for.as_ref(),
+        &queues.created_browser,
+        url,
     ) {
-        Some(browser) => browser,
+        Some(launched) => launched,
         None => {
             render::free_rd_texture(rd_texture_rid);
             return Err(CefError::BrowserCreationFailed(
@@ -689,7 +784,9 @@ fn create_accelerated_browser(
     };
 
     let event_queues = queues.event_queues.clone();
-    app.state = Some(BrowserState {
+    let pending_certificate_errors = queues.pending_certificate_errors.clone();
+    let pending_auth_requests = queues.pending_auth_requests.clone();
+    let into_state = move |browser| BrowserState {
         browser,
         render_mode: RenderMode::Accelerated {
             render_state,
@@ -704,6 +801,14 @@ fn create_accelerated_browser(
         popup_policy,
         pending_permission_requests,
         pending_permission_aggregates,
+        devtools_registration: None,
+        internal_devtools_results: Default::default(),
+        navigation_after_devtools_result: None,
+        pending_certificate_errors,
+        pending_auth_requests,
+    };
+    install_browser(app, launched, into_state, move || {
+        render::free_rd_texture(rd_texture_rid)
     });
     Ok(())
 }
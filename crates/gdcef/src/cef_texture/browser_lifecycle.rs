@@ -31,6 +31,7 @@ impl CefTexture {
 
         self.ime_active = false;
         self.ime_proxy = None;
+        self.stop_live_reload();
 
         if let Some(mut overlay) = self.popup_overlay.take() {
             overlay.queue_free();
@@ -66,10 +67,15 @@ impl CefTexture {
             logical_size,
             dpi,
             max_fps,
-            url: self.url.to_string(),
+            url: self.initial_url(),
+            profile: self.profile.to_string(),
+            proxy: self.proxy.to_string(),
+            accept_language: self.accept_language.to_string(),
             enable_accelerated_osr: self.enable_accelerated_osr,
             background_color: self.background_color,
             popup_policy: self.popup_policy,
+            request_hooks: self.request_hooks.clone(),
+            devtools_for: self.devtools_inspected.clone(),
             software_target_texture: None,
             log_prefix: "CefTexture",
         };
@@ -77,15 +83,36 @@ impl CefTexture {
             self.with_app_mut(|app| app.mark_browser_closed());
             return Err(err);
         }
+        self.last_size = logical_size;
+        self.last_dpi = dpi;
+        // DevTools browsers are adopted in `on_process` once CEF has created them
+        if self.with_app(|app| app.state.is_some()) {
+            self.finish_browser_create();
+        }
+        Ok(())
+    }
+
+    fn finish_browser_create(&mut self) {
         self.with_app_mut(|app| app.mark_browser_running());
+        self.apply_user_agent_override();
         if let Some(texture) =
             self.with_app(|app| app.state.as_ref().map(|s| s.render_mode.texture_2d()))
         {
             self.base_mut().set_texture(&texture);
         }
+    }
 
-        self.last_size = logical_size;
-        self.last_dpi = dpi;
-        Ok(())
+    /// Adopts a DevTools browser once CEF has created it. If it never arrives, the
+    /// node falls back to its own `url`.
+    pub(super) fn adopt_pending_browser(&mut self) {
+        match self.with_app_mut(backend::adopt_pending_browser) {
+            Some(Ok(())) => self.finish_browser_create(),
+            Some(Err(e)) => {
+                self.with_app_mut(|app| app.mark_browser_closed());
+                self.devtools_inspected = None;
+                godot::global::godot_error!("[CefTexture] {}", e);
+            }
+            None => {}
+        }
     }
 }
@@ -0,0 +1,27 @@
+use crate::browser::App;
+
+/// Continues or cancels the request behind a `certificate_error` signal.
+pub(crate) fn resolve_certificate_error(app: &App, request_id: i64, allow: bool) -> bool {
+    let Some(state) = app.state.as_ref() else {
+        godot::global::godot_warn!(
+            "[CefTexture] Cannot resolve certificate error {}: no active browser",
+            request_id
+        );
+        return false;
+    };
+    let Some(callback) = state.pending_certificate_errors.take(request_id) else {
+        godot::global::godot_warn!(
+            "[CefTexture] Unknown or stale certificate error request id: {}",
+            request_id
+        );
+        return false;
+    };
+
+    use cef::ImplCallback;
+    if allow {
+        callback.cont();
+    } else {
+        callback.cancel();
+    }
+    true
+}
@@ -0,0 +1,99 @@
+use godot::classes::Json;
+use godot::prelude::*;
+
+use super::{CefTexture, backend};
+use crate::browser::{App, BrowserState};
+
+/// Sends a DevTools protocol method to the browser and returns its message id, or 0 on failure.
+/// The result arrives through the observer registered on first use.
+pub(crate) fn execute_devtools_method(app: &mut App, method: &str, params: &VarDictionary) -> i32 {
+    let Some(state) = app.state.as_mut() else {
+        return 0;
+    };
+    let Some(host) = observed_host(state) else {
+        return 0;
+    };
+    send_devtools_method(&host, method, params)
+}
+
+/// Sends a DevTools protocol method on the node's own behalf; its result is not
+/// emitted as `devtools_result`. Returns the message id, or `None` on failure.
+pub(crate) fn execute_internal_devtools_method(
+    app: &mut App,
+    method: &str,
+    params: &VarDictionary,
+) -> Option<i32> {
+    let state = app.state.as_mut()?;
+    let host = observed_host(state)?;
+    let id = send_devtools_method(&host, method, params);
+    if id == 0 {
+        return None;
+    }
+    state.internal_devtools_results.insert(id);
+    Some(id)
+}
+
+/// Consumes the result of a call made with `execute_internal_devtools_method`, loading
+/// the URL that waited for it. Returns `false` if `id` belongs to a call from GDScript.
+pub(crate) fn take_internal_devtools_result(app: &mut App, id: i32) -> bool {
+    let Some(state) = app.state.as_mut() else {
+        return false;
+    };
+    if !state.internal_devtools_results.remove(&id) {
+        return false;
+    }
+    if let Some((_, url)) = state
+        .navigation_after_devtools_result
+        .take_if(|(pending, _)| *pending == id)
+    {
+        app.load_url(&url);
+    }
+    true
+}
+
+/// Returns the browser host, registering the DevTools observer on first use.
+fn observed_host(state: &mut BrowserState) -> Option<cef::BrowserHost> {
+    use cef::{ImplBrowser, ImplBrowserHost};
+    let host = state.browser.host()?;
+    if state.devtools_registration.is_none() {
+        let mut observer =
+            crate::devtools::DevToolsMessageObserverImpl::build(state.event_queues.clone());
+        state.devtools_registration = host.add_dev_tools_message_observer(Some(&mut observer));
+    }
+    Some(host)
+}
+
+fn send_devtools_method(host: &cef::BrowserHost, method: &str, params: &VarDictionary) -> i32 {
+    use cef::ImplBrowserHost;
+    let params_json = Json::stringify(&params.to_variant()).to_string();
+    let Some(mut params) = crate::devtools::dictionary_value_from_json(&params_json) else {
+        godot::global::godot_warn!(
+            "[CefTexture] DevTools params for '{}' are not a JSON object",
+            method
+        );
+        return 0;
+    };
+    let method = cef::CefString::from(method);
+    host.execute_dev_tools_method(0, Some(&method), Some(&mut params))
+}
+
+/// Parses a DevTools JSON payload into a Dictionary; anything else yields an empty one.
+pub(super) fn dictionary_from_json(json: &str) -> VarDictionary {
+    if json.is_empty() {
+        return VarDictionary::new();
+    }
+    Json::parse_string(json)
+        .try_to::<VarDictionary>()
+        .unwrap_or_default()
+}
+
+impl CefTexture {
+    /// Replaces this node's browser with the DevTools frontend of `inspected`.
+    /// The old browser is closed here and the new one is created lazily in `on_process`.
+    pub(super) fn show_devtools_for(&mut self, inspected: cef::BrowserHost) {
+        self.devtools_inspected = Some(inspected);
+        let mut popup_texture_2d_rd = self.popup_texture_2d_rd.take();
+        self.with_app_mut(|app| backend::close_runtime(app, popup_texture_2d_rd.as_mut()));
+        self.popup_texture_2d_rd = popup_texture_2d_rd;
+    }
+}
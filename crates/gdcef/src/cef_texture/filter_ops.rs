@@ -0,0 +1,205 @@
+//! Response filters for CefTexture.
+//!
+//! Requests whose URL matches a filter are fetched by a deferred resource
+//! handler instead of being loaded directly. Bodies whose MIME type also
+//! matches, up to `godot_cef/network/response_filter_max_size_mb`, are queued
+//! for the node and served once it has filtered them, so no CEF thread waits
+//! for the main thread. Matching callables are chained in the order they were
+//! added; each is called with `(url, mime_type, body)` and returns the new
+//! body, or `null` to keep it.
+//!
+//! Text bodies in UTF-8 or Latin-1 are passed as `String` and re-encoded in the
+//! response's charset. Every other body is passed as `PackedByteArray`.
+
+use godot::global::godot_warn;
+use godot::prelude::*;
+use std::collections::VecDeque;
+
+use super::CefTexture;
+use crate::browser::{FilteredResponseEvent, ResponseFilterPattern};
+
+pub(super) struct ResponseFilter {
+    pattern: ResponseFilterPattern,
+    handler: Callable,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum TextEncoding {
+    Utf8,
+    Latin1,
+}
+
+fn is_text_mime_type(mime_type: &str) -> bool {
+    mime_type.starts_with("text/")
+        || mime_type.ends_with("+json")
+        || mime_type.ends_with("+xml")
+        || matches!(
+            mime_type,
+            "application/javascript"
+                | "application/x-javascript"
+                | "application/ecmascript"
+                | "application/json"
+                | "application/xml"
+        )
+}
+
+/// Picks how a body is passed to filters. `None` passes the raw bytes.
+fn text_encoding(mime_type: &str, charset: &str, body: &[u8]) -> Option<TextEncoding> {
+    if !is_text_mime_type(mime_type) {
+        return None;
+    }
+    match charset.to_ascii_lowercase().as_str() {
+        "utf-8" | "utf8" => Some(TextEncoding::Utf8),
+        "iso-8859-1" | "latin1" | "us-ascii" => Some(TextEncoding::Latin1),
+        "" if std::str::from_utf8(body).is_ok() => Some(TextEncoding::Utf8),
+        _ => None,
+    }
+}
+
+fn decode(body: &[u8], encoding: TextEncoding) -> String {
+    match encoding {
+        TextEncoding::Utf8 => String::from_utf8_lossy(body).into_owned(),
+        TextEncoding::Latin1 => body.iter().map(|&byte| byte as char).collect(),
+    }
+}
+
+fn encode(text: &str, encoding: TextEncoding) -> Vec<u8> {
+    match encoding {
+        TextEncoding::Utf8 => text.as_bytes().to_vec(),
+        // Characters Latin-1 cannot represent are replaced like a browser would
+        TextEncoding::Latin1 => text
+            .chars()
+            .map(|c| u8::try_from(u32::from(c)).unwrap_or(b'?'))
+            .collect(),
+    }
+}
+
+/// Turns a filter's return value into the new body, or `None` to keep the current one.
+fn body_from_result(result: &Variant, encoding: Option<TextEncoding>) -> Option<Vec<u8>> {
+    match result.get_type() {
+        VariantType::NIL => None,
+        VariantType::STRING | VariantType::STRING_NAME => Some(encode(
+            &result.stringify().to_string(),
+            encoding.unwrap_or(TextEncoding::Utf8),
+        )),
+        VariantType::PACKED_BYTE_ARRAY => Some(result.to::<PackedByteArray>().to_vec()),
+        other => {
+            godot_warn!(
+                "[CefTexture] Response filter returned {:?}; expected null, a String or a PackedByteArray",
+                other
+            );
+            None
+        }
+    }
+}
+
+impl CefTexture {
+    pub(super) fn add_response_filter_impl(
+        &mut self,
+        url_pattern: String,
+        mime_filter: String,
+        handler: Callable,
+    ) {
+        let pattern = ResponseFilterPattern {
+            url_pattern,
+            mime_filter: mime_filter.to_ascii_lowercase(),
+        };
+        self.response_filters
+            .push(ResponseFilter { pattern, handler });
+        self.sync_response_filter_patterns();
+    }
+
+    pub(super) fn remove_response_filter_impl(&mut self, url_pattern: &str) -> bool {
+        let count = self.response_filters.len();
+        self.response_filters
+            .retain(|filter| filter.pattern.url_pattern != url_pattern);
+        self.sync_response_filter_patterns();
+        self.response_filters.len() != count
+    }
+
+    /// Publishes the current patterns to the request handler on the IO thread.
+    fn sync_response_filter_patterns(&self) {
+        if let Ok(mut patterns) = self.request_hooks.response_filters.write() {
+            *patterns = self
+                .response_filters
+                .iter()
+                .map(|filter| filter.pattern.clone())
+                .collect();
+        }
+    }
+
+    /// Runs the filters over each fetched body and serves the result.
+    pub(super) fn process_filtered_responses(
+        &mut self,
+        responses: &VecDeque<FilteredResponseEvent>,
+    ) {
+        for response in responses {
+            let handlers: Vec<Callable> = self
+                .response_filters
+                .iter()
+                .filter(|filter| filter.pattern.matches(&response.url, &response.mime_type))
+                .map(|filter| filter.handler.clone())
+                .collect();
+
+            let encoding = text_encoding(&response.mime_type, &response.charset, &response.body);
+            let mut body: Option<Vec<u8>> = None;
+            for handler in handlers.iter().filter(|handler| handler.is_valid()) {
+                let current = body.as_deref().unwrap_or(&response.body);
+                let current = match encoding {
+                    Some(encoding) => {
+                        GString::from(decode(current, encoding).as_str()).to_variant()
+                    }
+                    None => PackedByteArray::from(current).to_variant(),
+                };
+                let args = varray![
+                    GString::from(response.url.as_str()),
+                    GString::from(response.mime_type.as_str()),
+                    current
+                ];
+
+                // Lets the callable access this node while it runs
+                let result = {
+                    let _guard = self.base_mut();
+                    handler.callv(&args)
+                };
+                if let Some(filtered) = body_from_result(&result, encoding) {
+                    body = Some(filtered);
+                }
+            }
+
+            response.complete(body.unwrap_or_else(|| response.body.clone()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_encoding() {
+        assert_eq!(
+            text_encoding("text/html", "UTF-8", b""),
+            Some(TextEncoding::Utf8)
+        );
+        assert_eq!(
+            text_encoding("application/json", "iso-8859-1", b""),
+            Some(TextEncoding::Latin1)
+        );
+        assert_eq!(
+            text_encoding("image/svg+xml", "", b"<svg/>"),
+            Some(TextEncoding::Utf8)
+        );
+        assert_eq!(text_encoding("text/plain", "", b"\xff\xfe"), None);
+        assert_eq!(text_encoding("text/plain", "shift_jis", b"abc"), None);
+        assert_eq!(text_encoding("image/png", "utf-8", b"abc"), None);
+    }
+
+    #[test]
+    fn test_latin1_round_trip() {
+        let body = b"caf\xe9";
+        assert_eq!(decode(body, TextEncoding::Latin1), "café");
+        assert_eq!(encode("café", TextEncoding::Latin1), body);
+        assert_eq!(encode("a€b", TextEncoding::Latin1), b"a?b");
+    }
+}
@@ -0,0 +1,112 @@
+//! Request interception for CefTexture.
+//!
+//! Requests whose URL matches an interceptor pattern are paused on the CEF IO
+//! thread and queued for the node. Matching callables are called in the order
+//! they were added with `(method, url, headers, body)`; the first one that does
+//! not return `null` decides the request:
+//!
+//! - a `String` redirects the request to that URL
+//! - a `Dictionary` (`status`, `headers`, `body`) is served as the response
+
+use cef::ImplCallback;
+use godot::global::godot_warn;
+use godot::prelude::*;
+use std::collections::VecDeque;
+
+use super::CefTexture;
+use crate::browser::InterceptedRequestEvent;
+use crate::godot_protocol::{CustomSchemeResponse, glob_matches, response_from_dictionary};
+
+pub(super) struct RequestInterceptor {
+    pattern: String,
+    handler: Callable,
+}
+
+/// Turns an interceptor's return value into a response, or `None` to pass through.
+fn response_from_result(result: &Variant) -> Option<CustomSchemeResponse> {
+    match result.get_type() {
+        VariantType::NIL => None,
+        VariantType::STRING | VariantType::STRING_NAME => Some(CustomSchemeResponse::redirect(
+            &result.stringify().to_string(),
+        )),
+        VariantType::DICTIONARY => Some(response_from_dictionary(&result.to::<VarDictionary>())),
+        other => {
+            godot_warn!(
+                "[CefTexture] Request interceptor returned {:?}; expected null, a redirect URL or a response Dictionary",
+                other
+            );
+            None
+        }
+    }
+}
+
+impl CefTexture {
+    pub(super) fn add_request_interceptor_impl(&mut self, pattern: String, handler: Callable) {
+        self.request_interceptors
+            .push(RequestInterceptor { pattern, handler });
+        self.sync_request_interceptor_patterns();
+    }
+
+    pub(super) fn remove_request_interceptor_impl(&mut self, pattern: &str) -> bool {
+        let count = self.request_interceptors.len();
+        self.request_interceptors
+            .retain(|interceptor| interceptor.pattern != pattern);
+        self.sync_request_interceptor_patterns();
+        self.request_interceptors.len() != count
+    }
+
+    /// Publishes the current patterns to the request handler on the IO thread.
+    fn sync_request_interceptor_patterns(&self) {
+        if let Ok(mut patterns) = self.request_hooks.interceptor_patterns.write() {
+            *patterns = self
+                .request_interceptors
+                .iter()
+                .map(|interceptor| interceptor.pattern.clone())
+                .collect();
+        }
+    }
+
+    /// Runs the interceptors for each paused request and resumes it.
+    pub(super) fn process_intercepted_requests(
+        &mut self,
+        requests: &VecDeque<InterceptedRequestEvent>,
+    ) {
+        for request in requests {
+            let handlers: Vec<Callable> = self
+                .request_interceptors
+                .iter()
+                .filter(|interceptor| glob_matches(&interceptor.pattern, &request.url))
+                .map(|interceptor| interceptor.handler.clone())
+                .collect();
+
+            let mut headers = VarDictionary::new();
+            for (name, value) in &request.headers {
+                headers.set(name.as_str(), GString::from(value.as_str()).to_variant());
+            }
+            let args = varray![
+                GString::from(request.method.as_str()),
+                GString::from(request.url.as_str()),
+                headers,
+                PackedByteArray::from(request.body.as_slice())
+            ];
+
+            let mut response = None;
+            for handler in handlers.iter().filter(|handler| handler.is_valid()) {
+                // Lets the callable access this node while it runs
+                let result = {
+                    let _guard = self.base_mut();
+                    handler.callv(&args)
+                };
+                response = response_from_result(&result);
+                if response.is_some() {
+                    break;
+                }
+            }
+
+            if let Ok(mut slot) = request.response.lock() {
+                *slot = response;
+            }
+            request.callback.cont();
+        }
+    }
+}
@@ -0,0 +1,116 @@
+//! Live reload for pages served from `res://`.
+//!
+//! While `live_reload` is on (editor and debug runs only), the node polls the
+//! modification time of every `res://` file the scheme handler served to its
+//! browser. Changed stylesheets are swapped in place; any other change reloads
+//! the page without cache.
+
+use super::CefTexture;
+use cef::ImplBrowser;
+use godot::classes::{FileAccess, Json};
+use godot::prelude::*;
+use std::collections::HashMap;
+
+use crate::godot_protocol;
+use crate::utils::is_development_run;
+
+const POLL_INTERVAL_SECS: f64 = 0.5;
+
+#[derive(Default)]
+pub(super) struct LiveReloadState {
+    /// Browser whose served files are being recorded.
+    browser_id: Option<i32>,
+    /// Modification time of each watched file when it was last seen.
+    files: HashMap<String, u64>,
+    since_last_poll: f64,
+}
+
+/// Re-requests each `<link rel="stylesheet">` pointing at a changed file.
+/// Falls back to a reload when a stylesheet is not linked directly (e.g. `@import`).
+fn stylesheet_swap_script(paths: &[String]) -> String {
+    let paths: PackedStringArray = paths
+        .iter()
+        .map(|path| GString::from(path.as_str()))
+        .collect();
+    format!(
+        r#"(() => {{
+  const changed = new Set({});
+  const found = new Set();
+  for (const link of document.querySelectorAll('link[rel="stylesheet"]')) {{
+    const url = new URL(link.href, location.href);
+    const path = decodeURI(url.href.split(/[?#]/)[0]);
+    if (!changed.has(path)) continue;
+    found.add(path);
+    url.searchParams.set("live-reload", Date.now().toString());
+    link.href = url.href;
+  }}
+  if (found.size < changed.size) location.reload();
+}})();"#,
+        Json::stringify(&paths.to_variant())
+    )
+}
+
+fn modified_time(path: &str) -> u64 {
+    FileAccess::get_modified_time(&GString::from(path))
+}
+
+impl CefTexture {
+    /// Stops recording served files for this node's browser.
+    pub(super) fn stop_live_reload(&mut self) {
+        if let Some(browser_id) = self.live_reload_state.browser_id.take() {
+            godot_protocol::unwatch_served_files(browser_id);
+        }
+        self.live_reload_state.files.clear();
+    }
+
+    /// Called every frame; polls watched files and reloads when one changed.
+    pub(super) fn process_live_reload(&mut self) {
+        let browser_id = self.with_app(|app| app.browser().map(|b| b.identifier()));
+        if !self.live_reload || !is_development_run() || browser_id.is_none() {
+            self.stop_live_reload();
+            return;
+        }
+
+        if self.live_reload_state.browser_id != browser_id {
+            self.stop_live_reload();
+            if let Some(browser_id) = browser_id {
+                godot_protocol::watch_served_files(browser_id);
+            }
+            self.live_reload_state.browser_id = browser_id;
+        }
+
+        if let Some(browser_id) = browser_id {
+            for path in godot_protocol::take_served_files(browser_id) {
+                let time = modified_time(&path);
+                self.live_reload_state.files.insert(path, time);
+            }
+        }
+
+        self.live_reload_state.since_last_poll += self.base().get_process_delta_time();
+        if self.live_reload_state.since_last_poll < POLL_INTERVAL_SECS {
+            return;
+        }
+        self.live_reload_state.since_last_poll = 0.0;
+
+        let mut changed = Vec::new();
+        for (path, time) in self.live_reload_state.files.iter_mut() {
+            let current = modified_time(path);
+            if current != *time {
+                *time = current;
+                changed.push(path.clone());
+            }
+        }
+        changed.sort();
+
+        if changed.is_empty() {
+            return;
+        }
+        if changed.iter().all(|path| path.ends_with(".css")) {
+            self.eval(GString::from(stylesheet_swap_script(&changed).as_str()));
+        } else {
+            // The reload serves, and therefore records, the page's files again
+            self.live_reload_state.files.clear();
+            self.reload_ignore_cache();
+        }
+    }
+}
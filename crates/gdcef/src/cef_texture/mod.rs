@@ -1,7 +1,14 @@
+mod auth_ops;
 pub(crate) mod backend;
 mod browser_lifecycle;
+mod certificate_ops;
 mod cookie_ops;
+mod devtools_ops;
+mod filter_ops;
 mod ime;
+mod intercept_ops;
+mod live_reload;
+mod network_ops;
 mod permission_ops;
 mod rendering;
 mod signals;
@@ -17,7 +24,7 @@ use godot::classes::{
 use godot::prelude::*;
 
 use crate::cef_texture2d::CefTexture2D;
-use crate::{cef_init, input};
+use crate::{cef_init, godot_protocol, input};
 
 #[derive(GodotClass)]
 #[class(base=TextureRect)]
@@ -37,6 +44,13 @@ pub struct CefTexture {
     /// If false or unsupported, falls back to software rendering.
     enable_accelerated_osr: bool,
 
+    #[export]
+    #[var]
+    /// Storage profile of the browser. Empty keeps cookies and storage in memory only.
+    /// A name stores them under the data path, shared by every node using that name.
+    /// Takes effect when the browser is created.
+    profile: GString,
+
     #[export]
     #[var(get = get_background_color, set = set_background_color)]
     /// The background color of the browser view.
@@ -51,6 +65,36 @@ pub struct CefTexture {
     /// SignalOnly: emit `popup_requested` signal and let GDScript decide.
     popup_policy: i32,
 
+    #[export]
+    #[var]
+    /// Reloads the page when a `res://` file it loaded changes on disk.
+    /// Stylesheets are swapped in place; other files trigger `reload_ignore_cache()`.
+    /// Only active in the editor and in debug builds.
+    live_reload: bool,
+
+    #[export]
+    #[var(get = get_enable_resource_events, set = set_enable_resource_events)]
+    /// Emits `resource_load_started` and `resource_load_completed` for every
+    /// request the page makes. Off by default to avoid the per-request overhead.
+    enable_resource_events: bool,
+
+    #[export]
+    #[var(get = get_proxy, set = set_proxy)]
+    /// Proxy for the node's profile, e.g. `socks5://127.0.0.1:1080`, `direct://` or `system`.
+    /// Empty uses `godot_cef/network/proxy_server`. Can be changed at runtime.
+    proxy: GString,
+
+    #[export]
+    #[var(get = get_user_agent, set = set_user_agent)]
+    /// User agent of this browser. Empty uses the default. Can be changed at runtime.
+    user_agent: GString,
+
+    #[export]
+    #[var(get = get_accept_language, set = set_accept_language)]
+    /// Accept-Language for the node's profile, e.g. `de-DE,de,en`. Empty uses the default.
+    /// Can be changed at runtime.
+    accept_language: GString,
+
     #[var]
     /// Stores the IME cursor position in local coordinates (relative to this `CefTexture` node),
     /// automatically updated from the browser's caret position.
@@ -76,6 +120,16 @@ pub struct CefTexture {
     popup_texture: Option<Gd<ImageTexture>>,
     #[cfg(any(target_os = "macos", target_os = "windows", target_os = "linux"))]
     popup_texture_2d_rd: Option<Gd<godot::classes::Texture2Drd>>,
+
+    // Live reload state
+    live_reload_state: live_reload::LiveReloadState,
+
+    // Request interceptors; the patterns are shared with the request handler
+    request_interceptors: Vec<intercept_ops::RequestInterceptor>,
+    response_filters: Vec<filter_ops::ResponseFilter>,
+    request_hooks: crate::browser::RequestHooks,
+    // Browser whose DevTools this node shows, set by `open_devtools_in`
+    devtools_inspected: Option<cef::BrowserHost>,
     // Touch state
 
     // Find-in-page state
@@ -93,8 +147,14 @@ impl ITextureRect for CefTexture {
             base,
             url: "https://google.com".into(),
             enable_accelerated_osr: true,
+            profile: GString::new(),
             background_color: Color::from_rgba(0.0, 0.0, 0.0, 0.0),
             popup_policy: crate::browser::popup_policy::BLOCK,
+            live_reload: false,
+            enable_resource_events: false,
+            proxy: GString::new(),
+            user_agent: GString::new(),
+            accept_language: GString::new(),
             ime_position: Vector2i::new(0, 0),
             texture2d_helper,
             last_size: Vector2::ZERO,
@@ -109,6 +169,11 @@ impl ITextureRect for CefTexture {
             popup_texture: None,
             #[cfg(any(target_os = "macos", target_os = "windows", target_os = "linux"))]
             popup_texture_2d_rd: None,
+            live_reload_state: live_reload::LiveReloadState::default(),
+            request_interceptors: Vec::new(),
+            response_filters: Vec::new(),
+            request_hooks: Default::default(),
+            devtools_inspected: None,
         }
     }
 
@@ -151,7 +216,8 @@ impl ITextureRect for CefTexture {
     }
 }
 
-#[godot_api]
+// Signals are emitted by name, so no typed signal API is generated
+#[godot_api(no_typed_signals)]
 impl CefTexture {
     fn with_app<R>(&self, f: impl FnOnce(&crate::browser::App) -> R) -> R {
         let helper = self.texture2d_helper.bind();
@@ -181,6 +247,11 @@ impl CefTexture {
     #[signal]
     fn debug_ipc_message(event: Variant);
 
+    /// Emitted in debug builds and editor runs for console, navigation, network,
+    /// permission and popup events. Used by `CefIpcInspector` for its timeline.
+    #[signal]
+    fn debug_timeline_event(event: Variant);
+
     #[signal]
     fn url_changed(url: GString);
 
@@ -196,6 +267,27 @@ impl CefTexture {
     #[signal]
     fn load_error(url: GString, error_code: i32, error_text: GString);
 
+    /// Emitted when a request is about to be sent. Requires `enable_resource_events`.
+    #[signal]
+    fn resource_load_started(id: i64, url: GString, resource_type: GString, method: GString);
+
+    /// Emitted when a request finished, failed or was canceled. `error` is empty on
+    /// success, otherwise `canceled` or `failed`. Requires `enable_resource_events`.
+    #[signal]
+    fn resource_load_completed(
+        id: i64,
+        url: GString,
+        status: i32,
+        mime: GString,
+        received_bytes: i64,
+        duration_ms: i64,
+        error: GString,
+    );
+
+    /// Emitted when adblock cancels a request. `rule` is the matching filter.
+    #[signal]
+    fn request_blocked(url: GString, rule: GString);
+
     #[signal]
     fn console_message(level: u32, message: GString, source: GString, line: i32);
 
@@ -223,6 +315,23 @@ impl CefTexture {
     #[signal]
     fn permission_requested(permission_type: GString, url: GString, request_id: i64);
 
+    /// Emitted when a page's TLS certificate is invalid. The request waits until
+    /// `allow_certificate(request_id)` or `deny_certificate(request_id)` is called.
+    #[signal]
+    fn certificate_error(url: GString, error_code: i32, cert_info: VarDictionary, request_id: i64);
+
+    /// Emitted when a server or proxy asks for credentials. The request waits until
+    /// `provide_credentials(request_id, ...)` or `cancel_auth(request_id)` is called.
+    #[signal]
+    fn auth_requested(
+        request_id: i64,
+        host: GString,
+        port: i32,
+        realm: GString,
+        scheme: GString,
+        is_proxy: bool,
+    );
+
     /// Emitted after a find-in-page operation completes or is updated.
     ///
     /// - `count` is the total number of matches found.
@@ -248,6 +357,21 @@ impl CefTexture {
     #[signal]
     fn cookies_flushed();
 
+    /// Emitted when a method sent with `execute_devtools_method` completes.
+    /// On failure `result` holds the protocol error (`code`, `message`).
+    #[signal]
+    fn devtools_result(id: i32, success: bool, result: VarDictionary);
+
+    /// Emitted for events of DevTools domains enabled through `execute_devtools_method`,
+    /// e.g. `Network.requestWillBeSent` after `Network.enable`.
+    #[signal]
+    fn devtools_event(method: GString, params: VarDictionary);
+
+    /// Emitted after the page stored a file through a `PUT` or `POST` to `user://`.
+    /// Requires `godot_cef/schemes/user_writable_paths`.
+    #[signal]
+    fn user_file_written(path: GString);
+
     #[func]
     fn on_ready(&mut self) {
         use godot::classes::control::FocusMode;
@@ -274,9 +398,14 @@ impl CefTexture {
 
     #[func]
     fn on_process(&mut self) {
+        if self.with_app(|app| app.is_browser_pending()) {
+            do_message_loop_work();
+            self.adopt_pending_browser();
+        }
+
         // Lazy browser creation: if browser doesn't exist yet (e.g., size was 0 in on_ready
         // because we're inside a Container), try to create it now that layout may be complete.
-        if self.with_app(|app| app.state.is_none()) {
+        if self.with_app(|app| app.state.is_none() && !app.is_browser_pending()) {
             let size = self.base().get_size();
             if size.x > 0.0 && size.y > 0.0 && !self.browser_create_deferred_pending {
                 self.browser_create_deferred_pending = true;
@@ -293,12 +422,14 @@ impl CefTexture {
         if self.with_app(|app| app.state.is_some()) {
             do_message_loop_work();
         }
+        godot_protocol::process_pending_custom_scheme_requests();
 
         self.request_external_begin_frame();
         self.update_cursor();
 
         // Process all event queues with a single lock (more efficient than per-queue locks)
         self.process_all_event_queues();
+        self.process_live_reload();
     }
 
     #[func]
@@ -375,6 +506,8 @@ impl CefTexture {
     #[func]
     fn set_url_property(&mut self, url: GString) {
         self.url = url.clone();
+        // Files of the previous page no longer matter
+        self.stop_live_reload();
         self.texture2d_helper.bind_mut().set_url_property(url);
     }
 
@@ -757,6 +890,68 @@ impl CefTexture {
         self.with_app(|app| app.drag_state.is_drag_over)
     }
 
+    #[func]
+    fn get_enable_resource_events(&self) -> bool {
+        self.enable_resource_events
+    }
+
+    #[func]
+    fn set_enable_resource_events(&mut self, enabled: bool) {
+        use std::sync::atomic::Ordering;
+        self.enable_resource_events = enabled;
+        self.request_hooks
+            .resource_events
+            .store(enabled, Ordering::Relaxed);
+    }
+
+    #[func]
+    fn get_proxy(&self) -> GString {
+        self.proxy.clone()
+    }
+
+    #[func]
+    fn set_proxy(&mut self, proxy: GString) {
+        self.proxy = proxy;
+        let proxy = self.proxy.to_string();
+        self.with_app(|app| {
+            if app.state.is_some() {
+                network_ops::apply_proxy(app, &proxy);
+            }
+        });
+    }
+
+    #[func]
+    fn get_user_agent(&self) -> GString {
+        self.user_agent.clone()
+    }
+
+    #[func]
+    fn set_user_agent(&mut self, user_agent: GString) {
+        self.user_agent = user_agent;
+        let user_agent = self.user_agent.to_string();
+        self.with_app_mut(|app| {
+            if app.state.is_some() {
+                network_ops::apply_user_agent(app, &user_agent);
+            }
+        });
+    }
+
+    #[func]
+    fn get_accept_language(&self) -> GString {
+        self.accept_language.clone()
+    }
+
+    #[func]
+    fn set_accept_language(&mut self, accept_language: GString) {
+        self.accept_language = accept_language;
+        let accept_language = self.accept_language.to_string();
+        self.with_app(|app| {
+            if app.state.is_some() {
+                network_ops::apply_accept_language(app, &accept_language);
+            }
+        });
+    }
+
     #[func]
     fn get_popup_policy(&self) -> i32 {
         self.popup_policy
@@ -768,6 +963,51 @@ impl CefTexture {
         self.texture2d_helper.bind_mut().set_popup_policy(policy);
     }
 
+    /// Intercepts requests whose URL matches `url_pattern` (`*` matches any characters).
+    /// `handler` is called with `(method, url, headers, body)` and returns `null` to let
+    /// the request through, a URL `String` to redirect it, or a response `Dictionary`
+    /// with `status`, `headers` and `body`. Interceptors run in the order they were added.
+    #[func]
+    pub fn add_request_interceptor(&mut self, url_pattern: GString, handler: Callable) {
+        self.add_request_interceptor_impl(url_pattern.to_string(), handler);
+    }
+
+    /// Removes every interceptor added with `url_pattern`.
+    /// Returns `true` if any interceptor was removed.
+    #[func]
+    pub fn remove_request_interceptor(&mut self, url_pattern: GString) -> bool {
+        self.remove_request_interceptor_impl(&url_pattern.to_string())
+    }
+
+    /// Rewrites response bodies whose URL matches `url_pattern` and whose MIME type
+    /// matches `mime_filter` (`*` matches any characters, empty matches every type).
+    /// `handler` is called with `(url, mime_type, body)` and returns the new body, or
+    /// `null` to keep it. Text bodies are passed as `String`, others as `PackedByteArray`.
+    /// Filters run in the order they were added, each receiving the previous result.
+    #[func]
+    pub fn add_response_filter(
+        &mut self,
+        url_pattern: GString,
+        mime_filter: GString,
+        handler: Callable,
+    ) {
+        self.add_response_filter_impl(url_pattern.to_string(), mime_filter.to_string(), handler);
+    }
+
+    /// Re-reads the adblock rule lists into the engine shared by every browser,
+    /// e.g. after downloading an updated list. Returns `false` if adblock is disabled.
+    #[func]
+    pub fn reload_adblock_rules() -> bool {
+        crate::adblock_engine::reload_shared_engine("CefTexture")
+    }
+
+    /// Removes every response filter added with `url_pattern`.
+    /// Returns `true` if any filter was removed.
+    #[func]
+    pub fn remove_response_filter(&mut self, url_pattern: GString) -> bool {
+        self.remove_response_filter_impl(&url_pattern.to_string())
+    }
+
     #[func]
     pub fn grant_permission(&self, request_id: i64) -> bool {
         self.with_app(|app| permission_ops::resolve_permission_request(app, request_id, true))
@@ -778,6 +1018,56 @@ impl CefTexture {
         self.with_app(|app| permission_ops::resolve_permission_request(app, request_id, false))
     }
 
+    /// Continues the request of a `certificate_error` despite the invalid certificate.
+    #[func]
+    pub fn allow_certificate(&self, request_id: i64) -> bool {
+        self.with_app(|app| certificate_ops::resolve_certificate_error(app, request_id, true))
+    }
+
+    /// Cancels the request of a `certificate_error`.
+    #[func]
+    pub fn deny_certificate(&self, request_id: i64) -> bool {
+        self.with_app(|app| certificate_ops::resolve_certificate_error(app, request_id, false))
+    }
+
+    /// Allows certificate errors for `host` (`host`, `host:port` or `*.domain`) in every
+    /// browser without emitting `certificate_error`. Saved in the data path.
+    /// Editor and debug runs only.
+    #[func]
+    pub fn add_certificate_exception(&self, host: GString) -> bool {
+        crate::certificate::add_exception(&host.to_string())
+    }
+
+    /// Removes an exception added with `add_certificate_exception`.
+    #[func]
+    pub fn remove_certificate_exception(&self, host: GString) -> bool {
+        crate::certificate::remove_exception(&host.to_string())
+    }
+
+    /// Returns the exceptions added with `add_certificate_exception`.
+    #[func]
+    pub fn get_certificate_exceptions(&self) -> PackedStringArray {
+        crate::certificate::exceptions()
+            .iter()
+            .map(|host| GString::from(host.as_str()))
+            .collect()
+    }
+
+    /// Answers an `auth_requested` signal with a username and password.
+    #[func]
+    pub fn provide_credentials(&self, request_id: i64, user: GString, password: GString) -> bool {
+        let (user, password) = (user.to_string(), password.to_string());
+        self.with_app(|app| {
+            auth_ops::resolve_auth_request(app, request_id, Some((&user, &password)))
+        })
+    }
+
+    /// Cancels an `auth_requested` signal; the page receives the server's 401 or 407 response.
+    #[func]
+    pub fn cancel_auth(&self, request_id: i64) -> bool {
+        self.with_app(|app| auth_ops::resolve_auth_request(app, request_id, None))
+    }
+
     /// Retrieves all cookies. Results are emitted via `cookies_received` signal.
     /// Returns `true` if the request was initiated, `false` on failure.
     #[func]
@@ -837,6 +1127,31 @@ impl CefTexture {
         self.with_app(cookie_ops::flush_cookies)
     }
 
+    /// Calls a DevTools protocol method, e.g. `Emulation.setDeviceMetricsOverride`.
+    /// Returns the message id reported by `devtools_result`, or 0 on failure.
+    /// Works without the remote debugging port, including in release builds.
+    #[func]
+    pub fn execute_devtools_method(&mut self, method: GString, params: VarDictionary) -> i32 {
+        self.with_app_mut(|app| {
+            devtools_ops::execute_devtools_method(app, &method.to_string(), &params)
+        })
+    }
+
+    /// Shows the DevTools frontend for this browser inside `target`, replacing the page
+    /// `target` was showing. Returns `false` if this browser is not running yet.
+    #[func]
+    pub fn open_devtools_in(&mut self, mut target: Gd<CefTexture>) -> bool {
+        if target == self.to_gd() {
+            godot::global::godot_warn!("[CefTexture] Cannot open DevTools in the inspected node");
+            return false;
+        }
+        let Some(host) = self.with_app(|app| app.host()) else {
+            return false;
+        };
+        target.bind_mut().show_devtools_for(host);
+        true
+    }
+
     /// Called when the IME proxy LineEdit text changes during composition.
     #[func]
     fn on_ime_proxy_text_changed(&mut self, new_text: GString) {
@@ -0,0 +1,207 @@
+//! Runtime network overrides for CefTexture.
+//!
+//! `proxy` and `accept_language` are preferences of the request context, so
+//! they apply to every node sharing the node's `profile`. They are set on the
+//! context before the browser is created. `user_agent` is a DevTools emulation
+//! override and only applies to the node's own browser; a new browser starts on
+//! `about:blank` and loads `url` once the override is in place.
+
+use godot::prelude::*;
+
+use super::CefTexture;
+use crate::browser::App;
+
+const PROXY_PREFERENCE: &str = "proxy";
+const ACCEPT_LANGUAGE_PREFERENCE: &str = "intl.accept_languages";
+
+/// Entries of Chromium's `proxy` preference for a `proxy` property value.
+/// Empty returns `None`, which restores the default from the command line.
+fn proxy_preference(proxy: &str, bypass_list: &str) -> Option<Vec<(&'static str, String)>> {
+    match proxy {
+        "" => None,
+        "direct://" => Some(vec![("mode", "direct".to_string())]),
+        "system" => Some(vec![("mode", "system".to_string())]),
+        server => {
+            let mut entries = vec![
+                ("mode", "fixed_servers".to_string()),
+                ("server", server.to_string()),
+            ];
+            if !bypass_list.is_empty() {
+                entries.push(("bypass_list", bypass_list.to_string()));
+            }
+            Some(entries)
+        }
+    }
+}
+
+/// Sets a preference on `context`; `None` restores its default.
+fn set_context_preference(
+    context: &cef::RequestContext,
+    name: &str,
+    mut value: Option<cef::Value>,
+) -> bool {
+    use cef::ImplPreferenceManager;
+    let name = cef::CefString::from(name);
+    if context.can_set_preference(Some(&name)) == 0 {
+        godot::global::godot_warn!(
+            "[CefTexture] Preference '{}' cannot be changed at runtime; it is fixed by a project setting",
+            name
+        );
+        return false;
+    }
+    let mut error = cef::CefString::default();
+    if context.set_preference(Some(&name), value.as_mut(), Some(&mut error)) == 0 {
+        godot::global::godot_warn!(
+            "[CefTexture] Failed to set preference '{}': {}",
+            name,
+            error
+        );
+        return false;
+    }
+    true
+}
+
+fn string_value(text: &str) -> Option<cef::Value> {
+    use cef::ImplValue;
+    let value = cef::value_create()?;
+    value.set_string(Some(&cef::CefString::from(text)));
+    Some(value)
+}
+
+fn dictionary_value(entries: &[(&str, String)]) -> Option<cef::Value> {
+    use cef::{ImplDictionaryValue, ImplValue};
+    let mut dictionary = cef::dictionary_value_create()?;
+    for (key, text) in entries {
+        dictionary.set_string(
+            Some(&cef::CefString::from(*key)),
+            Some(&cef::CefString::from(text.as_str())),
+        );
+    }
+    let value = cef::value_create()?;
+    value.set_dictionary(Some(&mut dictionary));
+    Some(value)
+}
+
+fn set_proxy(context: &cef::RequestContext, proxy: &str) -> bool {
+    let bypass_list = crate::settings::get_proxy_bypass_list();
+    let value =
+        proxy_preference(proxy, &bypass_list).and_then(|entries| dictionary_value(&entries));
+    set_context_preference(context, PROXY_PREFERENCE, value)
+}
+
+fn set_accept_language(context: &cef::RequestContext, accept_language: &str) -> bool {
+    let value = if accept_language.is_empty() {
+        None
+    } else {
+        string_value(accept_language)
+    };
+    set_context_preference(context, ACCEPT_LANGUAGE_PREFERENCE, value)
+}
+
+fn browser_context(app: &App) -> Option<cef::RequestContext> {
+    use cef::ImplBrowserHost;
+    app.host()?.request_context()
+}
+
+pub(crate) fn apply_proxy(app: &App, proxy: &str) -> bool {
+    browser_context(app).is_some_and(|context| set_proxy(&context, proxy))
+}
+
+pub(crate) fn apply_accept_language(app: &App, accept_language: &str) -> bool {
+    browser_context(app).is_some_and(|context| set_accept_language(&context, accept_language))
+}
+
+/// Sets the overrides of a node on the request context its browser is about to use.
+/// Empty values are skipped so a new node does not reset a shared profile.
+pub(super) fn apply_context_overrides(
+    context: &cef::RequestContext,
+    proxy: &str,
+    accept_language: &str,
+) {
+    if !proxy.is_empty() {
+        set_proxy(context, proxy);
+    }
+    if !accept_language.is_empty() {
+        set_accept_language(context, accept_language);
+    }
+}
+
+/// Overrides the user agent through `Emulation.setUserAgentOverride`; empty clears the override.
+/// Returns the DevTools message id, or `None` on failure.
+pub(crate) fn apply_user_agent(app: &mut App, user_agent: &str) -> Option<i32> {
+    let params = vdict! { "userAgent": GString::from(user_agent) };
+    super::devtools_ops::execute_internal_devtools_method(
+        app,
+        "Emulation.setUserAgentOverride",
+        &params,
+    )
+}
+
+impl CefTexture {
+    /// Whether a new browser starts on `about:blank` so its first request to
+    /// `url` already carries the `user_agent` override.
+    fn defers_first_navigation(&self) -> bool {
+        !self.user_agent.is_empty() && self.devtools_inspected.is_none()
+    }
+
+    /// URL a new browser is created with.
+    pub(super) fn initial_url(&self) -> String {
+        if self.defers_first_navigation() {
+            "about:blank".to_string()
+        } else {
+            self.url.to_string()
+        }
+    }
+
+    /// Applies `user_agent` to a browser created with [`Self::initial_url`],
+    /// then loads `url` once the override is confirmed.
+    pub(super) fn apply_user_agent_override(&mut self) {
+        if !self.defers_first_navigation() {
+            return;
+        }
+        let user_agent = self.user_agent.to_string();
+        let url = self.url.to_string();
+        self.with_app_mut(|app| {
+            let Some(id) = apply_user_agent(app, &user_agent) else {
+                godot::global::godot_warn!(
+                    "[CefTexture] Failed to override the user agent; loading {} without it",
+                    url
+                );
+                app.load_url(&url);
+                return;
+            };
+            if let Some(state) = app.state.as_mut() {
+                state.navigation_after_devtools_result = Some((id, url));
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_proxy_preference() {
+        assert_eq!(proxy_preference("", "localhost"), None);
+        assert_eq!(
+            proxy_preference("direct://", "localhost"),
+            Some(vec![("mode", "direct".to_string())])
+        );
+        assert_eq!(
+            proxy_preference("socks5://127.0.0.1:1080", ""),
+            Some(vec![
+                ("mode", "fixed_servers".to_string()),
+                ("server", "socks5://127.0.0.1:1080".to_string()),
+            ])
+        );
+        assert_eq!(
+            proxy_preference("http://proxy:8080", "localhost,*.lan"),
+            Some(vec![
+                ("mode", "fixed_servers".to_string()),
+                ("server", "http://proxy:8080".to_string()),
+                ("bypass_list", "localhost,*.lan".to_string()),
+            ])
+        );
+    }
+}
@@ -3,11 +3,15 @@
 //! This module handles draining event queues and emitting Godot signals.
 
 use super::CefTexture;
+use cef::ImplBrowser;
 use godot::prelude::*;
 
 use std::collections::VecDeque;
 
-use crate::browser::{DebugIpcEvent, DragEvent, LoadingStateEvent};
+use crate::browser::{
+    BlockedRequestEvent, DebugIpcEvent, DebugTimelineEvent, DragEvent, LoadingStateEvent,
+    ResourceLoadEvent,
+};
 use crate::drag::DragDataInfo;
 
 macro_rules! emit_signal_variants {
@@ -145,23 +149,34 @@ impl CefTexture {
             std::mem::take(&mut *queues)
         };
 
+        // Resume paused requests first so page loads are not held up by signal handlers
+        self.process_intercepted_requests(&events.intercepted_requests);
+        self.process_filtered_responses(&events.filtered_responses);
+
         // Now process events without holding the lock
         self.emit_message_signals(&events.messages);
         self.emit_binary_message_signals(&events.binary_messages);
         self.emit_data_message_signals(&events.data_messages);
         self.emit_debug_ipc_signals(&events.debug_ipc_events);
+        self.emit_debug_timeline_signals(&events.debug_timeline_events);
         self.emit_url_change_signals(&events.url_changes);
         self.emit_title_change_signals(&events.title_changes);
         self.emit_loading_state_signals(&events.loading_states);
+        self.emit_resource_load_signals(&events.resource_loads);
+        self.emit_request_blocked_signals(&events.blocked_requests);
         self.emit_console_message_signals(&events.console_messages);
         self.emit_drag_event_signals(&events.drag_events);
         self.emit_popup_request_signals(&events.popup_requests);
         self.emit_permission_request_signals(&events.permission_requests);
+        self.emit_certificate_error_signals(&events.certificate_errors);
+        self.emit_auth_request_signals(&events.auth_requests);
         self.emit_find_result_signals(&events.find_results);
         self.emit_cookie_event_signals(&events.cookie_events);
+        self.emit_devtools_signals(&events.devtools_events);
         self.emit_download_request_signals(&events.download_requests);
         self.emit_download_update_signals(&events.download_updates);
         self.emit_render_process_terminated_signals(&events.render_process_terminated);
+        self.emit_user_file_written_signals();
 
         // Handle IME events (these may modify self state)
         self.process_ime_enable_events(&events.ime_enables);
@@ -210,11 +225,29 @@ impl CefTexture {
             payload.set("body", GString::from(&event.body).to_variant());
             payload.set("timestamp_unix_ms", event.timestamp_unix_ms.to_variant());
             payload.set("body_size_bytes", event.body_size_bytes.to_variant());
+            if let Some(data) = event.data.as_deref()
+                && let Ok(decoded) = crate::ipc_data::decode_cbor_bytes_to_variant(data)
+            {
+                payload.set("data", decoded);
+            }
             self.base_mut()
                 .emit_signal("debug_ipc_message", &[payload.to_variant()]);
         }
     }
 
+    fn emit_debug_timeline_signals(&mut self, events: &VecDeque<DebugTimelineEvent>) {
+        for event in events {
+            let mut payload = godot::builtin::VarDictionary::new();
+            payload.set("kind", GString::from(event.kind.as_str()).to_variant());
+            payload.set("name", GString::from(&event.name).to_variant());
+            payload.set("url", GString::from(&event.url).to_variant());
+            payload.set("detail", GString::from(&event.detail).to_variant());
+            payload.set("timestamp_unix_ms", event.timestamp_unix_ms.to_variant());
+            self.base_mut()
+                .emit_signal("debug_timeline_event", &[payload.to_variant()]);
+        }
+    }
+
     fn emit_url_change_signals(&mut self, urls: &VecDeque<String>) {
         for url in urls {
             emit_signal_variants!(self, "url_changed", GString::from(url));
@@ -261,6 +294,60 @@ impl CefTexture {
         }
     }
 
+    fn emit_resource_load_signals(&mut self, events: &VecDeque<ResourceLoadEvent>) {
+        for event in events {
+            match event {
+                ResourceLoadEvent::Started {
+                    id,
+                    url,
+                    resource_type,
+                    method,
+                } => {
+                    emit_signal_variants!(
+                        self,
+                        "resource_load_started",
+                        *id as i64,
+                        GString::from(url),
+                        GString::from(*resource_type),
+                        GString::from(method)
+                    );
+                }
+                ResourceLoadEvent::Completed {
+                    id,
+                    url,
+                    status,
+                    mime_type,
+                    received_bytes,
+                    duration_ms,
+                    error,
+                } => {
+                    emit_signal_variants!(
+                        self,
+                        "resource_load_completed",
+                        *id as i64,
+                        GString::from(url),
+                        status,
+                        GString::from(mime_type),
+                        received_bytes,
+                        duration_ms,
+                        GString::from(error)
+                    );
+                }
+            }
+        }
+    }
+
+    fn emit_request_blocked_signals(&mut self, events: &VecDeque<BlockedRequestEvent>) {
+        for event in events {
+            emit_signal_variants!(
+                self,
+                "request_blocked",
+                GString::from(&event.url),
+                GString::from(&event.rule)
+            );
+        }
+    }
+
     fn emit_console_message_signals(
         &mut self,
         events: &VecDeque<crate::browser::ConsoleMessageEvent>,
@@ -341,6 +428,58 @@ impl CefTexture {
         }
     }
 
+    fn emit_certificate_error_signals(
+        &mut self,
+        events: &VecDeque<crate::certificate::CertificateErrorEvent>,
+    ) {
+        for event in events {
+            // Nobody can answer, so fail the request like a browser without an override
+            if !self.base().has_connections("certificate_error") {
+                self.with_app(|app| {
+                    super::certificate_ops::resolve_certificate_error(app, event.request_id, false)
+                });
+                continue;
+            }
+            let cert_info = vdict! {
+                "subject": GString::from(&event.info.subject),
+                "issuer": GString::from(&event.info.issuer),
+                "valid_start": event.info.valid_start,
+                "valid_expiry": event.info.valid_expiry,
+                "cert_status": event.info.cert_status,
+            };
+            emit_signal_variants!(
+                self,
+                "certificate_error",
+                GString::from(&event.url),
+                event.error_code,
+                cert_info,
+                event.request_id
+            );
+        }
+    }
+
+    fn emit_auth_request_signals(&mut self, events: &VecDeque<crate::auth::AuthRequestEvent>) {
+        for event in events {
+            // Nobody can answer, so fail the request like a canceled login prompt
+            if !self.base().has_connections("auth_requested") {
+                self.with_app(|app| {
+                    super::auth_ops::resolve_auth_request(app, event.request_id, None)
+                });
+                continue;
+            }
+            emit_signal_variants!(
+                self,
+                "auth_requested",
+                event.request_id,
+                GString::from(&event.host),
+                event.port,
+                GString::from(&event.realm),
+                GString::from(&event.scheme),
+                event.is_proxy
+            );
+        }
+    }
+
     fn emit_find_result_signals(&mut self, events: &VecDeque<crate::browser::FindResultEvent>) {
         for event in events {
             emit_signal_variants!(
@@ -374,6 +513,31 @@ impl CefTexture {
         }
     }
 
+    fn emit_devtools_signals(&mut self, events: &VecDeque<crate::devtools::DevToolsEvent>) {
+        for event in events {
+            match event {
+                crate::devtools::DevToolsEvent::Result {
+                    id,
+                    success,
+                    result,
+                } => {
+                    let internal = self.with_app_mut(|app| {
+                        super::devtools_ops::take_internal_devtools_result(app, *id)
+                    });
+                    if internal {
+                        continue;
+                    }
+                    let result = super::devtools_ops::dictionary_from_json(result);
+                    emit_signal_variants!(self, "devtools_result", id, success, result);
+                }
+                crate::devtools::DevToolsEvent::Event { method, params } => {
+                    let params = super::devtools_ops::dictionary_from_json(params);
+                    emit_signal_variants!(self, "devtools_event", GString::from(method), params);
+                }
+            }
+        }
+    }
+
     fn emit_download_request_signals(
         &mut self,
         events: &VecDeque<crate::browser::DownloadRequestEvent>,
@@ -408,6 +572,16 @@ impl CefTexture {
         }
     }
 
+    fn emit_user_file_written_signals(&mut self) {
+        let Some(browser_id) = self.with_app(|app| app.browser().map(|b| b.identifier())) else {
+            return;
+        };
+
+        for path in crate::godot_protocol::take_user_files_written(browser_id) {
+            emit_signal_variants!(self, "user_file_written", GString::from(&path));
+        }
+    }
+
     fn process_ime_enable_events(&mut self, events: &VecDeque<bool>) {
         // Take the last event (latest wins)
         if let Some(&enable) = events.back() {
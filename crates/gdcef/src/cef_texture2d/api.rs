@@ -319,7 +319,7 @@ impl CefTexture2D {
                 .push_back(crate::browser::DebugIpcEvent::data_from_variant(
                     crate::browser::DebugIpcDirection::ToRenderer,
                     &data,
-                    bytes.len(),
+                    &bytes,
                 ));
             }
     }
@@ -1,4 +1,7 @@
-use cef::{ImplBrowser, ImplBrowserHost, ImplFrame, ImplListValue, ImplProcessMessage};
+use cef::{
+    ImplAuthCallback, ImplBrowser, ImplBrowserHost, ImplCallback, ImplFrame, ImplListValue,
+    ImplProcessMessage,
+};
 use godot::classes::image::Format as ImageFormat;
 use godot::classes::notify::ObjectNotification;
 use godot::classes::{
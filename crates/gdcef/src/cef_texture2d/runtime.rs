@@ -79,9 +79,14 @@ impl CefTextureRuntime {
             dpi,
             max_fps: backend::get_max_fps(),
             url: url.to_string(),
+            profile: String::new(),
+            proxy: String::new(),
+            accept_language: String::new(),
             enable_accelerated_osr,
             background_color,
             popup_policy,
+            request_hooks: Default::default(),
+            devtools_for: None,
             software_target_texture,
             log_prefix,
         };
@@ -123,6 +128,7 @@ impl CefTextureRuntime {
         if self.app.state.is_some() {
             cef::do_message_loop_work();
         }
+        crate::godot_protocol::process_pending_custom_scheme_requests();
         backend::request_external_begin_frame(&self.app);
     }
 
@@ -134,9 +140,17 @@ impl CefTextureRuntime {
     }
 
     pub(crate) fn drain_event_queues(&self, log_prefix: &str) {
-        let Some(event_queues) = self.app.state.as_ref().map(|state| &state.event_queues) else {
+        let Some(state) = self.app.state.as_ref() else {
             return;
         };
+        // No node answers these here; deny them so the page does not wait forever
+        for callback in state.pending_certificate_errors.drain() {
+            callback.cancel();
+        }
+        for callback in state.pending_auth_requests.drain() {
+            callback.cancel();
+        }
+        let event_queues = &state.event_queues;
 
         let Ok(mut queues) = event_queues.lock() else {
             godot::global::godot_warn!(
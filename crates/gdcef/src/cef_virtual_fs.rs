@@ -0,0 +1,39 @@
+use godot::prelude::*;
+
+use crate::godot_protocol;
+
+/// Serves files that are not on Godot's filesystem through `res://` and `user://`.
+///
+/// A mount overlays every URL under `prefix`. The source may be a `Dictionary`
+/// of path → `PackedByteArray`, an opened `ZIPReader`, or a directory path.
+/// Paths that are missing from the mount fall back to `FileAccess`.
+#[derive(GodotClass)]
+#[class(no_init)]
+pub struct CefVirtualFs {
+    base: Base<RefCounted>,
+}
+
+#[godot_api]
+impl CefVirtualFs {
+    /// Mounts `source` at `prefix` (e.g. `res://dlc/`), replacing any mount
+    /// at the same prefix. Returns `false` if the prefix or source is invalid.
+    #[func]
+    pub fn mount(prefix: GString, source: Variant) -> bool {
+        godot_protocol::mount(&prefix.to_string(), &source)
+    }
+
+    /// Removes the mount at `prefix`. Returns `false` if nothing was mounted there.
+    #[func]
+    pub fn unmount(prefix: GString) -> bool {
+        godot_protocol::unmount(&prefix.to_string())
+    }
+
+    /// Returns the normalized prefixes of all active mounts.
+    #[func]
+    pub fn get_mounts() -> PackedStringArray {
+        godot_protocol::mounted_prefixes()
+            .iter()
+            .map(|prefix| GString::from(prefix.as_str()))
+            .collect()
+    }
+}
@@ -0,0 +1,242 @@
+//! Certificate error handling for CEF integration.
+//!
+//! `RequestHandler::on_certificate_error` queues a `CertificateErrorEvent` and
+//! keeps CEF's callback until the node answers with `allow_certificate()` or
+//! `deny_certificate()`. Hosts listed in
+//! `godot_cef/security/certificate_exception_hosts`, or added at runtime with
+//! `add_certificate_exception()`, are allowed without asking. Runtime
+//! exceptions are stored in the data path and shared by every browser. They
+//! only apply in editor and debug runs, so an exported game never trusts a
+//! certificate because a page once asked for it.
+
+use cef::{self, *};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+const EXCEPTIONS_FILE_NAME: &str = "certificate-exceptions.txt";
+/// Microseconds between 1601-01-01, the epoch of `cef_basetime_t`, and the Unix epoch.
+const BASETIME_UNIX_EPOCH_OFFSET_US: i64 = 11_644_473_600_000_000;
+
+/// Exceptions added at runtime, loaded from the data path on first use.
+static STORED_EXCEPTIONS: Mutex<Option<Vec<String>>> = Mutex::new(None);
+
+/// The server certificate of a failed request, extracted for the main thread.
+#[derive(Debug, Clone, Default)]
+pub struct CertificateInfo {
+    pub subject: String,
+    pub issuer: String,
+    /// Unix timestamps in seconds, 0 if unknown.
+    pub valid_start: i64,
+    pub valid_expiry: i64,
+    /// `cef_cert_status_t` flags.
+    pub cert_status: i64,
+}
+
+impl CertificateInfo {
+    pub fn from_cef(ssl_info: &Sslinfo) -> Self {
+        let cert_status = i64::from(crate::cef_raw_to_u32!(ssl_info.cert_status().get_raw()));
+        let Some(certificate) = ssl_info.x509_certificate() else {
+            return Self {
+                cert_status,
+                ..Default::default()
+            };
+        };
+        let principal_name = |principal: Option<X509CertPrincipal>| {
+            principal
+                .map(|principal| CefStringUtf16::from(&principal.display_name()).to_string())
+                .unwrap_or_default()
+        };
+        Self {
+            subject: principal_name(certificate.subject()),
+            issuer: principal_name(certificate.issuer()),
+            valid_start: basetime_to_unix(certificate.valid_start()),
+            valid_expiry: basetime_to_unix(certificate.valid_expiry()),
+            cert_status,
+        }
+    }
+}
+
+/// A certificate error waiting for `allow_certificate()` or `deny_certificate()`.
+#[derive(Debug, Clone)]
+pub struct CertificateErrorEvent {
+    pub request_id: i64,
+    pub url: String,
+    pub error_code: i32,
+    pub info: CertificateInfo,
+}
+
+fn basetime_to_unix(time: Basetime) -> i64 {
+    if time.val == 0 {
+        return 0;
+    }
+    (time.val - BASETIME_UNIX_EPOCH_OFFSET_US).div_euclid(1_000_000)
+}
+
+/// Returns the lowercase host and the port of `url`.
+fn url_host(url: &str) -> Option<(String, Option<u16>)> {
+    let url = url::Url::parse(url).ok()?;
+    let host = url.host_str()?.to_ascii_lowercase();
+    Some((host, url.port_or_known_default()))
+}
+
+/// Matches `host`, `host:port`, `*.domain` and `*.domain:port` patterns.
+pub(crate) fn host_matches(pattern: &str, host: &str, port: Option<u16>) -> bool {
+    let pattern = pattern.to_ascii_lowercase();
+    let (pattern_host, pattern_port) = match pattern.rsplit_once(':') {
+        Some((pattern_host, pattern_port)) => match pattern_port.parse::<u16>() {
+            Ok(pattern_port) => (pattern_host, Some(pattern_port)),
+            Err(_) => return false,
+        },
+        None => (pattern.as_str(), None),
+    };
+    if pattern_port.is_some() && pattern_port != port {
+        return false;
+    }
+    match pattern_host.strip_prefix("*.") {
+        Some(domain) => host
+            .strip_suffix(domain)
+            .is_some_and(|subdomain| subdomain.ends_with('.')),
+        None => pattern_host == host,
+    }
+}
+
+fn exceptions_path() -> PathBuf {
+    crate::settings::get_data_path().join(EXCEPTIONS_FILE_NAME)
+}
+
+fn with_stored_exceptions<R>(f: impl FnOnce(&mut Vec<String>) -> R) -> Option<R> {
+    let mut stored = STORED_EXCEPTIONS.lock().ok()?;
+    let exceptions = stored.get_or_insert_with(|| {
+        fs::read_to_string(exceptions_path())
+            .map(|text| {
+                text.lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default()
+    });
+    Some(f(exceptions))
+}
+
+fn save_exceptions(exceptions: &[String]) {
+    let path = exceptions_path();
+    let mut text = exceptions.join("\n");
+    text.push('\n');
+    let result = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|()| fs::write(&path, text));
+    if let Err(error) = result {
+        godot::global::godot_warn!(
+            "[CefTexture] Failed to save certificate exceptions to '{}': {}",
+            path.display().to_string(),
+            error
+        );
+    }
+}
+
+/// Whether certificate errors for `url` are allowed without asking the node.
+pub(crate) fn is_exception(url: &str) -> bool {
+    let Some((host, port)) = url_host(url) else {
+        return false;
+    };
+    let configured = crate::settings::get_certificate_exception_hosts();
+    configured
+        .iter()
+        .any(|pattern| host_matches(pattern, &host, port))
+        || crate::utils::is_development_run()
+            && with_stored_exceptions(|stored| {
+                stored
+                    .iter()
+                    .any(|pattern| host_matches(pattern, &host, port))
+            })
+            .unwrap_or(false)
+}
+
+/// Adds a runtime exception and saves it. Returns `false` if it already existed
+/// or this is not a development run.
+pub(crate) fn add_exception(host: &str) -> bool {
+    let host = host.trim().to_ascii_lowercase();
+    if host.is_empty() {
+        return false;
+    }
+    if !crate::utils::is_development_run() {
+        godot::global::godot_warn!(
+            "[CefTexture] Certificate exceptions can only be added in editor and debug runs; use godot_cef/security/certificate_exception_hosts for '{}'",
+            host
+        );
+        return false;
+    }
+    with_stored_exceptions(|stored| {
+        if stored.contains(&host) {
+            return false;
+        }
+        stored.push(host);
+        save_exceptions(stored);
+        true
+    })
+    .unwrap_or(false)
+}
+
+/// Removes a runtime exception and saves the rest. Returns `false` if it did not exist.
+pub(crate) fn remove_exception(host: &str) -> bool {
+    let host = host.trim().to_ascii_lowercase();
+    with_stored_exceptions(|stored| {
+        let count = stored.len();
+        stored.retain(|entry| *entry != host);
+        if stored.len() == count {
+            return false;
+        }
+        save_exceptions(stored);
+        true
+    })
+    .unwrap_or(false)
+}
+
+/// Returns the runtime exceptions; configured hosts are not included.
+pub(crate) fn exceptions() -> Vec<String> {
+    with_stored_exceptions(|stored| stored.clone()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_host_matches() {
+        assert!(host_matches("localhost", "localhost", Some(5173)));
+        assert!(host_matches("LocalHost:5173", "localhost", Some(5173)));
+        assert!(!host_matches("localhost:5173", "localhost", Some(443)));
+        assert!(host_matches("*.dev.local", "api.dev.local", Some(443)));
+        assert!(!host_matches("*.dev.local", "dev.local", Some(443)));
+        assert!(!host_matches("*.dev.local", "evildev.local", Some(443)));
+        assert!(!host_matches("localhost:https", "localhost", Some(443)));
+    }
+
+    #[test]
+    fn test_url_host() {
+        assert_eq!(
+            url_host("https://Dev.Local:8443/app"),
+            Some(("dev.local".to_string(), Some(8443)))
+        );
+        assert_eq!(
+            url_host("https://example.com/"),
+            Some(("example.com".to_string(), Some(443)))
+        );
+        assert_eq!(url_host("not a url"), None);
+    }
+
+    #[test]
+    fn test_basetime_to_unix() {
+        assert_eq!(basetime_to_unix(Basetime { val: 0 }), 0);
+        assert_eq!(
+            basetime_to_unix(Basetime {
+                val: BASETIME_UNIX_EPOCH_OFFSET_US + 1_700_000_000_000_000
+            }),
+            1_700_000_000
+        );
+    }
+}
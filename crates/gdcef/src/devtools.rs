@@ -0,0 +1,79 @@
+//! DevTools protocol access for CEF integration.
+//!
+//! Results of `BrowserHost::execute_dev_tools_method` calls and events from
+//! enabled DevTools domains reach a `DevToolsMessageObserver` on the CEF UI
+//! thread. They are queued as raw JSON and parsed on Godot's main thread.
+
+use cef::{self, *};
+
+use crate::browser::EventQueuesHandle;
+
+/// DevTools messages consumed by the Godot main thread.
+#[derive(Debug, Clone)]
+pub enum DevToolsEvent {
+    /// Result of a method call. `result` is the JSON result, or the error object on failure.
+    Result {
+        id: i32,
+        success: bool,
+        result: String,
+    },
+    /// An event from an enabled domain, e.g. `Network.responseReceived`.
+    Event { method: String, params: String },
+}
+
+fn json_from_bytes(data: Option<&[u8]>) -> String {
+    data.map(|bytes| String::from_utf8_lossy(bytes).into_owned())
+        .unwrap_or_default()
+}
+
+/// Parses a JSON object into the `DictionaryValue` passed to `execute_dev_tools_method`.
+pub(crate) fn dictionary_value_from_json(json: &str) -> Option<DictionaryValue> {
+    let json = CefString::from(json);
+    parse_json(Some(&json), JsonParserOptions::default())?.dictionary()
+}
+
+wrap_dev_tools_message_observer! {
+    pub(crate) struct DevToolsMessageObserverImpl {
+        event_queues: EventQueuesHandle,
+    }
+
+    impl DevToolsMessageObserver {
+        fn on_dev_tools_method_result(
+            &self,
+            _browser: Option<&mut Browser>,
+            message_id: ::std::os::raw::c_int,
+            success: ::std::os::raw::c_int,
+            result: Option<&[u8]>,
+        ) {
+            let event = DevToolsEvent::Result {
+                id: message_id,
+                success: success != 0,
+                result: json_from_bytes(result),
+            };
+            if let Ok(mut queues) = self.event_queues.lock() {
+                queues.devtools_events.push_back(event);
+            }
+        }
+
+        fn on_dev_tools_event(
+            &self,
+            _browser: Option<&mut Browser>,
+            method: Option<&CefString>,
+            params: Option<&[u8]>,
+        ) {
+            let event = DevToolsEvent::Event {
+                method: method.map(|method| method.to_string()).unwrap_or_default(),
+                params: json_from_bytes(params),
+            };
+            if let Ok(mut queues) = self.event_queues.lock() {
+                queues.devtools_events.push_back(event);
+            }
+        }
+    }
+}
+
+impl DevToolsMessageObserverImpl {
+    pub fn build(event_queues: EventQueuesHandle) -> DevToolsMessageObserver {
+        Self::new(event_queues)
+    }
+}
@@ -0,0 +1,213 @@
+//! Access policy for `user://`.
+//!
+//! `user://` holds saves and config, so by default only pages served from
+//! `res://` may read it. Projects can widen this with an origin allow-list
+//! (`godot_cef/schemes/user_allowed_origins`) and narrow it with a path
+//! allow-list (`godot_cef/schemes/user_allowed_paths`). Both lists hold
+//! patterns where `*` matches any run of characters.
+
+use std::fmt;
+use url::Url;
+
+use super::headers::glob_matches;
+
+/// Serializes the origin of `url` as `scheme://host[:port]`.
+///
+/// Unlike `Url::origin`, this also works for `res://` and custom schemes,
+/// which Chromium treats as standard schemes with a host.
+pub(crate) fn url_origin(url: &str) -> Option<String> {
+    let parsed = Url::parse(url).ok()?;
+    let host = parsed.host_str().unwrap_or("");
+    Some(match parsed.port() {
+        Some(port) => format!("{}://{}:{}", parsed.scheme(), host, port),
+        None => format!("{}://{}", parsed.scheme(), host),
+    })
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum AccessDenied {
+    /// The initiating origin is not in the origin allow-list.
+    Origin(String),
+    /// The request did not come from a document and was not a navigation.
+    UnknownOrigin,
+    /// The path is outside the path allow-list.
+    Path,
+}
+
+impl fmt::Display for AccessDenied {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AccessDenied::Origin(origin) => write!(
+                f,
+                "origin '{}' is not listed in godot_cef/schemes/user_allowed_origins",
+                origin
+            ),
+            AccessDenied::UnknownOrigin => write!(f, "the request has no initiating origin"),
+            AccessDenied::Path => {
+                write!(
+                    f,
+                    "path is not listed in godot_cef/schemes/user_allowed_paths"
+                )
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct UserAccessPolicy {
+    pub allowed_origins: Vec<String>,
+    /// Empty means every `user://` path is allowed.
+    pub allowed_paths: Vec<String>,
+}
+
+impl UserAccessPolicy {
+    pub fn from_settings() -> Self {
+        Self {
+            allowed_origins: crate::settings::get_user_allowed_origins(),
+            allowed_paths: crate::settings::get_user_allowed_paths(),
+        }
+    }
+
+    /// Checks a request for `godot_path` made by `origin`.
+    ///
+    /// A request without an origin is only allowed when it is a navigation,
+    /// i.e. the app itself set the URL of an empty browser.
+    pub fn check(
+        &self,
+        origin: Option<&str>,
+        godot_path: &str,
+        is_navigation: bool,
+    ) -> Result<(), AccessDenied> {
+        match origin {
+            Some(origin) => {
+                if !self
+                    .allowed_origins
+                    .iter()
+                    .any(|pattern| glob_matches(pattern, origin))
+                {
+                    return Err(AccessDenied::Origin(origin.to_string()));
+                }
+            }
+            None if !is_navigation => return Err(AccessDenied::UnknownOrigin),
+            None => {}
+        }
+
+        if !self.allowed_paths.is_empty()
+            && !self
+                .allowed_paths
+                .iter()
+                .any(|pattern| glob_matches(pattern, godot_path))
+        {
+            return Err(AccessDenied::Path);
+        }
+
+        Ok(())
+    }
+}
+
+/// Picks the origin a request was made from.
+///
+/// Prefers the `Origin` header, then the URL of the requesting frame.
+/// Opaque (`null`) origins and blank documents count as no origin.
+pub(crate) fn initiator_origin(origin_header: &str, frame_url: Option<&str>) -> Option<String> {
+    let origin_header = origin_header.trim();
+    if !origin_header.is_empty() && origin_header != "null" {
+        return Some(origin_header.trim_end_matches('/').to_string());
+    }
+
+    frame_url
+        .filter(|url| !url.is_empty() && !url.starts_with("about:"))
+        .and_then(url_origin)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(origins: &[&str], paths: &[&str]) -> UserAccessPolicy {
+        UserAccessPolicy {
+            allowed_origins: origins.iter().map(|s| s.to_string()).collect(),
+            allowed_paths: paths.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_url_origin() {
+        assert_eq!(
+            url_origin("res://ui/index.html"),
+            Some("res://ui".to_string())
+        );
+        assert_eq!(
+            url_origin("https://example.com:8443/a?b"),
+            Some("https://example.com:8443".to_string())
+        );
+        assert_eq!(
+            url_origin("http://localhost:5173/"),
+            Some("http://localhost:5173".to_string())
+        );
+        assert_eq!(url_origin("not a url"), None);
+    }
+
+    #[test]
+    fn test_initiator_origin() {
+        assert_eq!(
+            initiator_origin("https://example.com", Some("res://ui/index.html")),
+            Some("https://example.com".to_string())
+        );
+        assert_eq!(
+            initiator_origin("", Some("res://ui/index.html")),
+            Some("res://ui".to_string())
+        );
+        assert_eq!(
+            initiator_origin("null", Some("https://a.test/")),
+            Some("https://a.test".to_string())
+        );
+        assert_eq!(initiator_origin("", Some("about:blank")), None);
+        assert_eq!(initiator_origin("", None), None);
+    }
+
+    #[test]
+    fn test_default_policy_allows_only_res() {
+        let policy = policy(&["res://*"], &[]);
+        assert!(
+            policy
+                .check(Some("res://ui"), "user://save.json", false)
+                .is_ok()
+        );
+        assert_eq!(
+            policy.check(Some("https://evil.test"), "user://save.json", false),
+            Err(AccessDenied::Origin("https://evil.test".to_string()))
+        );
+        assert_eq!(
+            policy.check(None, "user://save.json", false),
+            Err(AccessDenied::UnknownOrigin)
+        );
+        assert!(policy.check(None, "user://ui/index.html", true).is_ok());
+    }
+
+    #[test]
+    fn test_origin_and_path_allow_lists() {
+        let policy = policy(
+            &["res://*", "https://*.example.com"],
+            &["user://ui-cache/*"],
+        );
+        assert!(
+            policy
+                .check(
+                    Some("https://cdn.example.com"),
+                    "user://ui-cache/app.js",
+                    false
+                )
+                .is_ok()
+        );
+        assert_eq!(
+            policy.check(Some("res://ui"), "user://saves/slot1.json", false),
+            Err(AccessDenied::Path)
+        );
+        assert!(
+            policy
+                .check(Some("https://example.org"), "user://ui-cache/app.js", false)
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,301 @@
+//! HTTP validators and conditional request handling.
+//!
+//! Derives `ETag` / `Last-Modified` values for Godot files and evaluates
+//! `If-None-Match`, `If-Modified-Since` and `If-Range` according to RFC 9110.
+
+use godot::classes::{FileAccess, Os};
+use godot::obj::Singleton;
+use godot::prelude::*;
+use std::path::Path;
+use std::sync::OnceLock;
+
+const WEEKDAYS: [&str; 7] = ["Thu", "Fri", "Sat", "Sun", "Mon", "Tue", "Wed"];
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// Newest modification time of the executable and its main pack, or 0 when
+/// neither is known. Packed resources only change with them.
+static PACK_STAMP: OnceLock<u64> = OnceLock::new();
+
+/// Cache validators for a served file.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct Validators {
+    pub etag: Option<String>,
+    /// Modification time in Unix seconds, `None` when unknown (e.g. packed files).
+    pub modified_time: Option<u64>,
+}
+
+impl Validators {
+    pub fn last_modified(&self) -> Option<String> {
+        self.modified_time.map(format_http_date)
+    }
+}
+
+fn compute_pack_stamp() -> u64 {
+    let executable = Os::singleton().get_executable_path().to_string();
+    let executable = Path::new(&executable);
+    let mut candidates = vec![executable.to_path_buf(), executable.with_extension("pck")];
+    // macOS bundles keep the pack in Contents/Resources
+    if let (Some(dir), Some(stem)) = (executable.parent(), executable.file_stem()) {
+        candidates.push(dir.join("../Resources").join(stem).with_extension("pck"));
+    }
+    candidates
+        .iter()
+        .map(|path| FileAccess::get_modified_time(&GString::from(path.to_string_lossy().as_ref())))
+        .max()
+        .unwrap_or(0)
+}
+
+/// Computes the pack stamp. Called on the main thread when the scheme
+/// handlers are registered, so the IO thread only reads the cached value.
+pub(crate) fn init_pack_stamp() {
+    PACK_STAMP.get_or_init(compute_pack_stamp);
+}
+
+/// Computes validators for `path`. Files without a modification time
+/// (resources inside a `.pck`) are tagged by their size and the pack stamp.
+pub(crate) fn file_validators(path: &str, size: u64) -> Validators {
+    let modified_time = FileAccess::get_modified_time(&GString::from(path));
+    if modified_time > 0 {
+        return Validators {
+            etag: Some(build_etag(size, modified_time)),
+            modified_time: Some(modified_time),
+        };
+    }
+
+    match PACK_STAMP.get_or_init(compute_pack_stamp) {
+        0 => Validators::default(),
+        stamp => Validators {
+            etag: Some(build_packed_etag(size, *stamp)),
+            modified_time: None,
+        },
+    }
+}
+
+pub(crate) fn build_etag(size: u64, modified_time: u64) -> String {
+    format!("\"{:x}-{:x}\"", size, modified_time)
+}
+
+pub(crate) fn build_packed_etag(size: u64, pack_stamp: u64) -> String {
+    format!("\"{:x}-pck{:x}\"", size, pack_stamp)
+}
+
+/// Formats Unix seconds as an IMF-fixdate, e.g. `Sun, 06 Nov 1994 08:49:37 GMT`.
+pub(crate) fn format_http_date(unix_secs: u64) -> String {
+    let days = unix_secs / 86_400;
+    let secs_of_day = unix_secs % 86_400;
+    let (year, month, day) = civil_from_days(days);
+
+    format!(
+        "{}, {:02} {} {} {:02}:{:02}:{:02} GMT",
+        WEEKDAYS[(days % 7) as usize],
+        day,
+        MONTHS[(month - 1) as usize],
+        year,
+        secs_of_day / 3600,
+        (secs_of_day % 3600) / 60,
+        secs_of_day % 60
+    )
+}
+
+/// Parses an IMF-fixdate into Unix seconds. Obsolete RFC 850 and asctime
+/// formats are not accepted; Chromium echoes back the value we sent.
+pub(crate) fn parse_http_date(value: &str) -> Option<u64> {
+    let (_, rest) = value.trim().split_once(", ")?;
+    let parts: Vec<&str> = rest.split(' ').collect();
+    if parts.len() != 5 || parts[4] != "GMT" {
+        return None;
+    }
+
+    let day: u64 = parts[0].parse().ok()?;
+    let month = MONTHS.iter().position(|m| *m == parts[1])? as u64 + 1;
+    let year: u64 = parts[2].parse().ok()?;
+    // IMF-fixdate years have four digits; larger ones would overflow below
+    if !(1970..=9999).contains(&year) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    let time: Vec<&str> = parts[3].split(':').collect();
+    if time.len() != 3 {
+        return None;
+    }
+    let hours: u64 = time[0].parse().ok()?;
+    let minutes: u64 = time[1].parse().ok()?;
+    let seconds: u64 = time[2].parse().ok()?;
+    if hours > 23 || minutes > 59 || seconds > 60 {
+        return None;
+    }
+
+    let days = days_from_civil(year, month, day);
+    Some(days * 86_400 + hours * 3600 + minutes * 60 + seconds)
+}
+
+/// Howard Hinnant's `civil_from_days`, restricted to dates after the Unix epoch.
+fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z % 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Howard Hinnant's `days_from_civil`, restricted to dates after the Unix epoch.
+fn days_from_civil(year: u64, month: u64, day: u64) -> u64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year / 400;
+    let yoe = year % 400;
+    let mp = if month > 2 { month - 3 } else { month + 9 };
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    (era * 146_097 + doe).saturating_sub(719_468)
+}
+
+fn strip_weak(tag: &str) -> &str {
+    tag.strip_prefix("W/").unwrap_or(tag)
+}
+
+/// Weak comparison of an `If-None-Match` list against `etag`.
+pub(crate) fn etag_matches_any(header: &str, etag: &str) -> bool {
+    header.split(',').map(str::trim).any(|candidate| {
+        candidate == "*" || (!candidate.is_empty() && strip_weak(candidate) == strip_weak(etag))
+    })
+}
+
+/// Returns `true` when a GET/HEAD request should be answered with 304.
+///
+/// `If-None-Match` takes precedence; `If-Modified-Since` is only consulted
+/// when no entity tag condition was sent.
+pub(crate) fn is_not_modified(
+    if_none_match: &str,
+    if_modified_since: &str,
+    validators: &Validators,
+) -> bool {
+    if !if_none_match.trim().is_empty() {
+        return validators
+            .etag
+            .as_deref()
+            .is_some_and(|etag| etag_matches_any(if_none_match, etag));
+    }
+
+    match (validators.modified_time, parse_http_date(if_modified_since)) {
+        (Some(modified_time), Some(since)) => modified_time <= since,
+        _ => false,
+    }
+}
+
+/// Returns `true` when a `Range` header may be honored given `If-Range`.
+///
+/// An entity tag requires a strong match; a date must equal `Last-Modified`.
+pub(crate) fn if_range_matches(if_range: &str, validators: &Validators) -> bool {
+    let if_range = if_range.trim();
+    if if_range.is_empty() {
+        return true;
+    }
+
+    if if_range.starts_with('"') || if_range.starts_with("W/") {
+        return !if_range.starts_with("W/")
+            && validators
+                .etag
+                .as_deref()
+                .is_some_and(|etag| !etag.starts_with("W/") && etag == if_range);
+    }
+
+    match (validators.modified_time, parse_http_date(if_range)) {
+        (Some(modified_time), Some(date)) => modified_time == date,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn validators(etag: &str, modified_time: Option<u64>) -> Validators {
+        Validators {
+            etag: Some(etag.to_string()),
+            modified_time,
+        }
+    }
+
+    #[test]
+    fn test_format_http_date() {
+        assert_eq!(format_http_date(0), "Thu, 01 Jan 1970 00:00:00 GMT");
+        assert_eq!(
+            format_http_date(784_111_777),
+            "Sun, 06 Nov 1994 08:49:37 GMT"
+        );
+        assert_eq!(
+            format_http_date(951_782_400),
+            "Tue, 29 Feb 2000 00:00:00 GMT"
+        );
+    }
+
+    #[test]
+    fn test_parse_http_date_round_trip() {
+        for secs in [0, 784_111_777, 951_782_400, 1_700_000_000, 4_102_444_800] {
+            assert_eq!(parse_http_date(&format_http_date(secs)), Some(secs));
+        }
+    }
+
+    #[test]
+    fn test_parse_http_date_rejects_malformed() {
+        assert_eq!(parse_http_date(""), None);
+        assert_eq!(parse_http_date("Sunday, 06-Nov-94 08:49:37 GMT"), None);
+        assert_eq!(parse_http_date("Sun Nov  6 08:49:37 1994"), None);
+        assert_eq!(parse_http_date("Sun, 06 Foo 1994 08:49:37 GMT"), None);
+        assert_eq!(parse_http_date("Sun, 06 Nov 1994 25:49:37 GMT"), None);
+        assert_eq!(parse_http_date("Sun, 06 Nov 1994 08:49:37 PST"), None);
+        assert_eq!(
+            parse_http_date("Mon, 01 Jan 999999999999999 00:00:00 GMT"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_etag_matches_any() {
+        let etag = build_etag(1024, 1_700_000_000);
+        assert!(etag_matches_any(&etag, &etag));
+        assert!(etag_matches_any(&format!("W/{}", etag), &etag));
+        assert!(etag_matches_any(&format!("\"other\", {}", etag), &etag));
+        assert!(etag_matches_any("*", &etag));
+        assert!(!etag_matches_any("\"other\"", &etag));
+        assert!(!etag_matches_any("", &etag));
+    }
+
+    #[test]
+    fn test_is_not_modified_prefers_if_none_match() {
+        let v = validators("\"a\"", Some(1000));
+        let later = format_http_date(2000);
+        assert!(is_not_modified("\"a\"", "", &v));
+        // A mismatching ETag wins over a satisfied date condition
+        assert!(!is_not_modified("\"b\"", &later, &v));
+        assert!(is_not_modified("", &later, &v));
+        assert!(is_not_modified("", &format_http_date(1000), &v));
+        assert!(!is_not_modified("", &format_http_date(999), &v));
+    }
+
+    #[test]
+    fn test_is_not_modified_without_modified_time() {
+        let v = validators(&build_packed_etag(1024, 1_700_000_000), None);
+        assert!(!is_not_modified("", &format_http_date(2000), &v));
+        assert!(is_not_modified("\"400-pck6553f100\"", "", &v));
+    }
+
+    #[test]
+    fn test_if_range_matches() {
+        let v = validators("\"a\"", Some(1000));
+        assert!(if_range_matches("", &v));
+        assert!(if_range_matches("\"a\"", &v));
+        assert!(!if_range_matches("W/\"a\"", &v));
+        assert!(!if_range_matches("\"b\"", &v));
+        assert!(if_range_matches(&format_http_date(1000), &v));
+        assert!(!if_range_matches(&format_http_date(2000), &v));
+        assert!(!if_range_matches("garbage", &v));
+    }
+}
@@ -0,0 +1,803 @@
+//! Project-defined custom schemes backed by GDScript callables.
+//!
+//! Schemes listed in `godot_cef/schemes/custom_schemes` are registered with CEF
+//! at startup. Requests arrive on the CEF IO thread, are queued, and are answered
+//! on the main thread by the `Callable` registered through `CefSchemeRegistry`.
+//! A handler either returns a response `Dictionary` directly or `await`s and
+//! returns it later, in which case the request stays pending until the
+//! coroutine completes.
+//!
+//! The same deferred resource handler also answers `res://` requests that are
+//! proxied to a development server (see [`super::dev_proxy`]), `res://` files
+//! converted from their imported resource (see [`super::imported`]), requests
+//! answered by a `CefTexture` request interceptor, and requests whose body a
+//! `CefTexture` response filter rewrites (see [`super::upstream`]).
+
+use cef::{
+    CefStringUtf16, ImplCallback, ImplPostData, ImplPostDataElement, ImplRequest,
+    ImplResourceHandler, ImplResponse, ImplSchemeHandlerFactory, ImplUrlrequest, ResourceHandler,
+    SchemeHandlerFactory, WrapResourceHandler, WrapSchemeHandlerFactory, rc::Rc,
+    wrap_resource_handler, wrap_scheme_handler_factory,
+};
+use godot::builtin::{PackedByteArray, VarDictionary, Variant, VariantType};
+use godot::classes::{Object, RefCounted};
+use godot::prelude::*;
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+use super::dev_proxy::{DevServerTarget, start_dev_server_request};
+use super::handler::status_text;
+use super::imported::{ImportedRequest, process_pending_conversions, queue_conversion};
+use super::upstream::start_fetch;
+
+const DEFAULT_MIME_TYPE: &str = "application/octet-stream";
+
+/// Response produced by a scheme handler callable.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct CustomSchemeResponse {
+    pub status: i32,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl CustomSchemeResponse {
+    pub(super) fn error(status: i32, message: &str) -> Self {
+        Self {
+            status,
+            headers: vec![("Content-Type".to_string(), "text/plain".to_string())],
+            body: message.as_bytes().to_vec(),
+        }
+    }
+
+    pub(crate) fn redirect(location: &str) -> Self {
+        Self {
+            status: 307,
+            headers: vec![("Location".to_string(), location.to_string())],
+            body: Vec::new(),
+        }
+    }
+
+    /// Target of a redirect response, if this is one.
+    fn redirect_location(&self) -> Option<&str> {
+        if !(300..400).contains(&self.status) {
+            return None;
+        }
+        self.headers
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case("Location"))
+            .map(|(_, value)| value.as_str())
+            .filter(|location| !location.is_empty())
+    }
+
+    fn content_type(&self) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case("Content-Type"))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// MIME type from the `Content-Type` header, without parameters.
+    pub(crate) fn mime_type(&self) -> String {
+        self.content_type()
+            .and_then(|value| value.split(';').next())
+            .map(|mime| mime.trim().to_string())
+            .filter(|mime| !mime.is_empty())
+            .unwrap_or_else(|| DEFAULT_MIME_TYPE.to_string())
+    }
+
+    /// `charset` parameter of the `Content-Type` header, empty if there is none.
+    pub(crate) fn charset(&self) -> String {
+        self.content_type()
+            .into_iter()
+            .flat_map(|value| value.split(';').skip(1))
+            .filter_map(|param| param.split_once('='))
+            .find(|(name, _)| name.trim().eq_ignore_ascii_case("charset"))
+            .map(|(_, charset)| charset.trim().trim_matches('"').to_string())
+            .unwrap_or_default()
+    }
+}
+
+/// Builds a response from the `Dictionary` returned by a handler:
+/// `{ "status": int, "headers": Dictionary, "body": PackedByteArray | String }`.
+pub(crate) fn response_from_dictionary(dict: &VarDictionary) -> CustomSchemeResponse {
+    let status = dict
+        .get("status")
+        .filter(|value| value.get_type() == VariantType::INT)
+        .map(|value| value.to::<i64>() as i32)
+        .unwrap_or(200);
+
+    let headers = dict
+        .get("headers")
+        .filter(|value| value.get_type() == VariantType::DICTIONARY)
+        .map(|value| {
+            value
+                .to::<VarDictionary>()
+                .iter_shared()
+                .map(|(name, value)| (name.stringify().to_string(), value.stringify().to_string()))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let body = match dict.get("body") {
+        Some(value) if value.get_type() == VariantType::PACKED_BYTE_ARRAY => {
+            value.to::<PackedByteArray>().to_vec()
+        }
+        Some(value) if value.get_type() == VariantType::STRING => {
+            value.to::<GString>().to_string().into_bytes()
+        }
+        _ => Vec::new(),
+    };
+
+    CustomSchemeResponse {
+        status,
+        headers,
+        body,
+    }
+}
+
+#[derive(Default)]
+pub(super) struct CustomRequestState {
+    response: Option<CustomSchemeResponse>,
+    /// Network error served instead of a response.
+    error: Option<cef::Errorcode>,
+    callback: Option<cef::Callback>,
+    /// In-flight request to a development server, kept so it can be canceled.
+    upstream_request: Option<cef::Urlrequest>,
+    offset: usize,
+    canceled: bool,
+}
+
+pub(super) type CustomRequestStateHandle = Arc<Mutex<CustomRequestState>>;
+
+/// Completes a deferred request from any thread, e.g. once the main thread
+/// has filtered a fetched response.
+#[derive(Clone)]
+pub(crate) struct DeferredResponse(CustomRequestStateHandle);
+
+impl DeferredResponse {
+    pub(super) fn new(state: &CustomRequestStateHandle) -> Self {
+        Self(state.clone())
+    }
+
+    pub(crate) fn complete(&self, response: CustomSchemeResponse) {
+        complete_request(&self.0, response);
+    }
+
+    /// Fails the request with `error` instead of serving a response.
+    pub(crate) fn fail(&self, error: cef::Errorcode) {
+        let callback = {
+            let Ok(mut state) = self.0.lock() else {
+                return;
+            };
+            if state.canceled {
+                return;
+            }
+            state.error = Some(error);
+            state.upstream_request = None;
+            state.callback.take()
+        };
+
+        if let Some(callback) = callback {
+            callback.cont();
+        }
+    }
+}
+
+/// Decides how a fetched response is served: right away, or later through the
+/// `DeferredResponse`.
+pub(crate) type FetchedResponseHook =
+    Arc<dyn Fn(CustomSchemeResponse, DeferredResponse) + Send + Sync>;
+
+struct PendingCustomRequest {
+    scheme: String,
+    method: String,
+    url: String,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+    state: CustomRequestStateHandle,
+}
+
+/// Requests waiting to be dispatched on the main thread.
+static PENDING_REQUESTS: Mutex<VecDeque<PendingCustomRequest>> = Mutex::new(VecDeque::new());
+
+thread_local! {
+    /// Handlers by scheme name. Callables are only touched on the main thread.
+    static SCHEME_HANDLERS: RefCell<HashMap<String, Callable>> = RefCell::new(HashMap::new());
+    /// Responders kept alive while their coroutine is pending.
+    static PENDING_RESPONDERS: RefCell<Vec<Gd<CefSchemeResponder>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Normalizes user input such as `Game://` or `game:` to `game`.
+pub(crate) fn normalize_scheme_name(scheme: &str) -> String {
+    scheme
+        .trim()
+        .trim_end_matches("://")
+        .trim_end_matches(':')
+        .to_ascii_lowercase()
+}
+
+pub(crate) fn register_custom_scheme_handler(scheme: &str, handler: Callable) {
+    SCHEME_HANDLERS.with(|handlers| {
+        handlers
+            .borrow_mut()
+            .insert(normalize_scheme_name(scheme), handler);
+    });
+}
+
+pub(crate) fn unregister_custom_scheme_handler(scheme: &str) -> bool {
+    SCHEME_HANDLERS.with(|handlers| {
+        handlers
+            .borrow_mut()
+            .remove(&normalize_scheme_name(scheme))
+            .is_some()
+    })
+}
+
+pub(super) fn complete_request(state: &CustomRequestStateHandle, response: CustomSchemeResponse) {
+    let callback = {
+        let Ok(mut state) = state.lock() else {
+            return;
+        };
+        if state.canceled {
+            return;
+        }
+        state.response = Some(response);
+        state.upstream_request = None;
+        state.callback.take()
+    };
+
+    if let Some(callback) = callback {
+        callback.cont();
+    }
+}
+
+pub(super) fn is_request_canceled(state: &CustomRequestStateHandle) -> bool {
+    state.lock().map(|state| state.canceled).unwrap_or(true)
+}
+
+/// Completes the request from a handler's return value, or waits for the
+/// coroutine when the handler used `await`.
+fn resolve_handler_result(state: &CustomRequestStateHandle, result: Variant) {
+    match result.get_type() {
+        VariantType::DICTIONARY => {
+            complete_request(
+                state,
+                response_from_dictionary(&result.to::<VarDictionary>()),
+            );
+        }
+        VariantType::OBJECT => {
+            let Ok(mut coroutine) = result.try_to::<Gd<Object>>() else {
+                complete_request(
+                    state,
+                    CustomSchemeResponse::error(500, "Invalid handler result"),
+                );
+                return;
+            };
+            if !coroutine.has_signal("completed") {
+                complete_request(
+                    state,
+                    CustomSchemeResponse::error(500, "Scheme handler must return a Dictionary"),
+                );
+                return;
+            }
+
+            let responder = Gd::from_init_fn(|base| CefSchemeResponder {
+                base,
+                state: state.clone(),
+                done: false,
+            });
+            coroutine.connect("completed", &responder.callable("_on_completed"));
+            PENDING_RESPONDERS.with(|responders| responders.borrow_mut().push(responder));
+        }
+        _ => complete_request(
+            state,
+            CustomSchemeResponse::error(500, "Scheme handler must return a Dictionary"),
+        ),
+    }
+}
+
+fn dispatch_request(request: PendingCustomRequest) {
+    if is_request_canceled(&request.state) {
+        return;
+    }
+
+    let handler = SCHEME_HANDLERS.with(|handlers| handlers.borrow().get(&request.scheme).cloned());
+    let Some(handler) = handler.filter(|handler| handler.is_valid()) else {
+        let message = format!("No handler registered for scheme: {}", request.scheme);
+        complete_request(&request.state, CustomSchemeResponse::error(404, &message));
+        return;
+    };
+
+    let mut headers = VarDictionary::new();
+    for (name, value) in &request.headers {
+        headers.set(name.as_str(), GString::from(value.as_str()).to_variant());
+    }
+    let body = PackedByteArray::from(request.body.as_slice());
+
+    let result = handler.callv(&varray![
+        GString::from(request.method.as_str()),
+        GString::from(request.url.as_str()),
+        headers,
+        body
+    ]);
+    resolve_handler_result(&request.state, result);
+}
+
+/// Dispatches queued custom scheme requests to their GDScript handlers.
+/// Must be called on the main thread, once per frame.
+pub(crate) fn process_pending_custom_scheme_requests() {
+    let pending: Vec<PendingCustomRequest> = match PENDING_REQUESTS.lock() {
+        Ok(mut queue) => queue.drain(..).collect(),
+        Err(_) => return,
+    };
+
+    for request in pending {
+        dispatch_request(request);
+    }
+    process_pending_conversions();
+
+    PENDING_RESPONDERS.with(|responders| {
+        responders.borrow_mut().retain(|responder| {
+            let responder = responder.bind();
+            !responder.done && !is_request_canceled(&responder.state)
+        });
+    });
+}
+
+/// Waits for a handler coroutine and completes its request.
+#[derive(GodotClass)]
+#[class(no_init)]
+pub struct CefSchemeResponder {
+    base: Base<RefCounted>,
+    state: CustomRequestStateHandle,
+    done: bool,
+}
+
+#[godot_api]
+impl CefSchemeResponder {
+    #[func]
+    fn _on_completed(&mut self, result: Variant) {
+        // Released on the next `process_pending_custom_scheme_requests`, not here,
+        // since this object may hold the last reference to itself.
+        self.done = true;
+        resolve_handler_result(&self.state, result);
+    }
+}
+
+pub(crate) fn read_request_headers(request: &cef::Request) -> Vec<(String, String)> {
+    let mut header_map = cef::CefStringMultimap::new();
+    request.header_map(Some(&mut header_map));
+    header_map
+        .into_iter()
+        .flat_map(|(name, values)| values.into_iter().map(move |value| (name.clone(), value)))
+        .collect()
+}
+
+pub(crate) fn read_request_body(request: &cef::Request) -> Vec<u8> {
+    let Some(post_data) = request.post_data() else {
+        return Vec::new();
+    };
+
+    let mut elements = vec![None; post_data.element_count()];
+    post_data.elements(Some(&mut elements));
+
+    let mut body = Vec::new();
+    for element in elements.into_iter().flatten() {
+        if element.get_type() != cef::PostdataelementType::BYTES {
+            continue;
+        }
+        let size = element.bytes_count();
+        let mut chunk = vec![0u8; size];
+        let copied = element.bytes(size, chunk.as_mut_ptr().cast());
+        chunk.truncate(copied);
+        body.extend_from_slice(&chunk);
+    }
+    body
+}
+
+/// Where a deferred response comes from.
+#[derive(Clone)]
+enum ResponseSource {
+    /// The GDScript handler registered for this scheme.
+    Script(String),
+    /// A development server that a `res://` prefix is proxied to.
+    DevServer(DevServerTarget),
+    /// A `res://` file converted from its imported resource on the main thread.
+    Imported(ImportedRequest),
+    /// The original request, fetched through the browser's request context.
+    Fetched {
+        context: Option<cef::RequestContext>,
+        on_fetched: FetchedResponseHook,
+    },
+    /// A response that is already known when the handler is created.
+    Immediate,
+}
+
+#[derive(Clone)]
+pub struct CustomSchemeResourceHandler {
+    source: ResponseSource,
+    state: CustomRequestStateHandle,
+}
+
+impl CustomSchemeResourceHandler {
+    pub fn new(scheme: &str) -> Self {
+        Self {
+            source: ResponseSource::Script(scheme.to_string()),
+            state: Arc::new(Mutex::new(CustomRequestState::default())),
+        }
+    }
+
+    pub(super) fn for_dev_server(target: DevServerTarget) -> Self {
+        if target.redirect {
+            // Answered right away so the page loads the module from the dev server itself
+            return Self::immediate(CustomSchemeResponse::redirect(&target.url));
+        }
+        Self {
+            source: ResponseSource::DevServer(target),
+            state: Arc::new(Mutex::new(CustomRequestState::default())),
+        }
+    }
+
+    pub(super) fn for_imported_resource(request: ImportedRequest) -> Self {
+        Self {
+            source: ResponseSource::Imported(request),
+            state: Arc::new(Mutex::new(CustomRequestState::default())),
+        }
+    }
+
+    /// Fetches the request itself and passes the response to `on_fetched`.
+    pub(crate) fn fetched(
+        context: Option<cef::RequestContext>,
+        on_fetched: FetchedResponseHook,
+    ) -> Self {
+        Self {
+            source: ResponseSource::Fetched {
+                context,
+                on_fetched,
+            },
+            state: Arc::new(Mutex::new(CustomRequestState::default())),
+        }
+    }
+
+    /// Stores `callback`, then starts an upstream request kept for `cancel`.
+    fn start_upstream(
+        &self,
+        callback: Option<&mut cef::Callback>,
+        start: impl FnOnce(&CustomRequestStateHandle) -> Option<cef::Urlrequest>,
+    ) {
+        if let Ok(mut state) = self.state.lock() {
+            state.callback = callback.cloned();
+        }
+        let upstream = start(&self.state);
+        if let Ok(mut state) = self.state.lock()
+            && state.response.is_none()
+        {
+            state.upstream_request = upstream;
+        }
+    }
+
+    pub(crate) fn immediate(response: CustomSchemeResponse) -> Self {
+        Self {
+            source: ResponseSource::Immediate,
+            state: Arc::new(Mutex::new(CustomRequestState {
+                response: Some(response),
+                ..Default::default()
+            })),
+        }
+    }
+}
+
+wrap_resource_handler! {
+    pub struct CustomSchemeResourceHandlerImpl {
+        handler: CustomSchemeResourceHandler,
+    }
+
+    impl ResourceHandler {
+        fn open(
+            &self,
+            request: Option<&mut cef::Request>,
+            handle_request: Option<&mut ::std::os::raw::c_int>,
+            callback: Option<&mut cef::Callback>,
+        ) -> ::std::os::raw::c_int {
+            let Some(request) = request else {
+                return false as _;
+            };
+
+            let scheme = match &self.handler.source {
+                ResponseSource::Script(scheme) => scheme,
+                ResponseSource::Immediate => {
+                    if let Some(handle_request) = handle_request {
+                        *handle_request = true as _;
+                    }
+                    return true as _;
+                }
+                ResponseSource::DevServer(target) => {
+                    self.handler.start_upstream(callback, |state| start_dev_server_request(request, target, state));
+
+                    if let Some(handle_request) = handle_request {
+                        *handle_request = false as _;
+                    }
+                    return true as _;
+                }
+                ResponseSource::Fetched { context, on_fetched } => {
+                    self.handler.start_upstream(callback, |state| start_fetch(request, context.clone(), on_fetched.clone(), state));
+
+                    if let Some(handle_request) = handle_request {
+                        *handle_request = false as _;
+                    }
+                    return true as _;
+                }
+                ResponseSource::Imported(imported) => {
+                    if let Ok(mut state) = self.handler.state.lock() {
+                        state.callback = callback.cloned();
+                    }
+                    queue_conversion(imported.clone(), self.handler.state.clone());
+
+                    if let Some(handle_request) = handle_request {
+                        *handle_request = false as _;
+                    }
+                    return true as _;
+                }
+            };
+
+            let pending = PendingCustomRequest {
+                scheme: scheme.clone(),
+                method: CefStringUtf16::from(&request.method()).to_string(),
+                url: CefStringUtf16::from(&request.url()).to_string(),
+                headers: read_request_headers(request),
+                body: read_request_body(request),
+                state: self.handler.state.clone(),
+            };
+
+            // Store the callback before queueing so the main thread can always continue us
+            if let Ok(mut state) = self.handler.state.lock() {
+                state.callback = callback.cloned();
+            }
+            if let Ok(mut queue) = PENDING_REQUESTS.lock() {
+                queue.push_back(pending);
+            }
+
+            // Handled asynchronously; `callback.cont()` runs once the handler responds
+            if let Some(handle_request) = handle_request {
+                *handle_request = false as _;
+            }
+            true as _
+        }
+
+        fn response_headers(
+            &self,
+            response: Option<&mut cef::Response>,
+            response_length: Option<&mut i64>,
+            redirect_url: Option<&mut cef::CefStringUtf16>,
+        ) {
+            let Ok(state) = self.handler.state.lock() else {
+                return;
+            };
+            if let Some(error) = state.error {
+                if let Some(response) = response {
+                    response.set_error(error);
+                }
+                if let Some(response_length) = response_length {
+                    *response_length = 0;
+                }
+                return;
+            }
+            let fallback;
+            let custom_response = match state.response.as_ref() {
+                Some(custom_response) => custom_response,
+                None => {
+                    fallback = CustomSchemeResponse::error(500, "Scheme handler did not respond");
+                    &fallback
+                }
+            };
+
+            if let Some(response) = response {
+                response.set_status(custom_response.status);
+                response.set_status_text(Some(&status_text(custom_response.status).into()));
+                response.set_mime_type(Some(&custom_response.mime_type().as_str().into()));
+                for (name, value) in &custom_response.headers {
+                    response.set_header_by_name(Some(&name.as_str().into()), Some(&value.as_str().into()), true as _);
+                }
+            }
+
+            if let (Some(redirect_url), Some(location)) = (redirect_url, custom_response.redirect_location()) {
+                *redirect_url = location.into();
+            }
+
+            if let Some(response_length) = response_length {
+                *response_length = custom_response.body.len() as i64;
+            }
+        }
+
+        fn read(
+            &self,
+            data_out: *mut u8,
+            bytes_to_read: ::std::os::raw::c_int,
+            bytes_read: Option<&mut ::std::os::raw::c_int>,
+            _callback: Option<&mut cef::ResourceReadCallback>,
+        ) -> ::std::os::raw::c_int {
+            if data_out.is_null() {
+                return false as _;
+            }
+            let Ok(mut state) = self.handler.state.lock() else {
+                return false as _;
+            };
+
+            let offset = state.offset;
+            let to_copy = match state.response.as_ref() {
+                Some(response) => {
+                    let to_copy = response.body.len().saturating_sub(offset).min(bytes_to_read.max(0) as usize);
+                    unsafe {
+                        std::ptr::copy_nonoverlapping(response.body.as_ptr().add(offset), data_out, to_copy);
+                    }
+                    to_copy
+                }
+                None => 0,
+            };
+            state.offset += to_copy;
+
+            if let Some(bytes_read) = bytes_read {
+                *bytes_read = to_copy as _;
+            }
+            (to_copy > 0) as _
+        }
+
+        fn skip(
+            &self,
+            bytes_to_skip: i64,
+            bytes_skipped: Option<&mut i64>,
+            _callback: Option<&mut cef::ResourceSkipCallback>,
+        ) -> ::std::os::raw::c_int {
+            let Ok(mut state) = self.handler.state.lock() else {
+                return false as _;
+            };
+
+            let remaining = state
+                .response
+                .as_ref()
+                .map(|response| response.body.len().saturating_sub(state.offset))
+                .unwrap_or(0);
+            let to_skip = remaining.min(bytes_to_skip.max(0) as usize);
+            state.offset += to_skip;
+
+            if let Some(bytes_skipped) = bytes_skipped {
+                *bytes_skipped = to_skip as i64;
+            }
+            true as _
+        }
+
+        fn cancel(&self) {
+            let upstream = match self.handler.state.lock() {
+                Ok(mut state) => {
+                    state.canceled = true;
+                    state.callback = None;
+                    state.response = None;
+                    state.upstream_request.take()
+                }
+                Err(_) => None,
+            };
+            // Canceled outside the lock: the client completes the request synchronously
+            if let Some(upstream) = upstream {
+                upstream.cancel();
+            }
+        }
+    }
+}
+
+impl CustomSchemeResourceHandlerImpl {
+    pub fn build(handler: CustomSchemeResourceHandler) -> ResourceHandler {
+        Self::new(handler)
+    }
+}
+
+#[derive(Clone)]
+pub struct CustomSchemeHandler {
+    scheme: String,
+}
+
+wrap_scheme_handler_factory! {
+    pub struct CustomSchemeHandlerFactory {
+        handler: CustomSchemeHandler,
+    }
+
+    impl SchemeHandlerFactory {
+        fn create(
+            &self,
+            _browser: Option<&mut cef::Browser>,
+            _frame: Option<&mut cef::Frame>,
+            _scheme_name: Option<&cef::CefString>,
+            _request: Option<&mut cef::Request>,
+        ) -> Option<ResourceHandler> {
+            Some(CustomSchemeResourceHandlerImpl::build(CustomSchemeResourceHandler::new(&self.handler.scheme)))
+        }
+    }
+}
+
+impl CustomSchemeHandlerFactory {
+    pub fn build(handler: CustomSchemeHandler) -> SchemeHandlerFactory {
+        Self::new(handler)
+    }
+}
+
+/// Registers a handler factory for every scheme declared in project settings.
+pub fn register_custom_scheme_handlers_on_context(context: &mut cef::RequestContext) {
+    use cef::ImplRequestContext;
+    for scheme in crate::settings::get_custom_schemes() {
+        let scheme_name: cef::CefString = scheme.as_str().into();
+        let mut factory = CustomSchemeHandlerFactory::build(CustomSchemeHandler { scheme });
+        context.register_scheme_handler_factory(
+            Some(&scheme_name),
+            Some(&"".into()),
+            Some(&mut factory),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_scheme_name() {
+        assert_eq!(normalize_scheme_name("game"), "game");
+        assert_eq!(normalize_scheme_name(" Game:// "), "game");
+        assert_eq!(normalize_scheme_name("api:"), "api");
+    }
+
+    #[test]
+    fn test_response_mime_type() {
+        let mut response = CustomSchemeResponse {
+            status: 200,
+            headers: vec![(
+                "content-type".to_string(),
+                "application/json; charset=utf-8".to_string(),
+            )],
+            body: Vec::new(),
+        };
+        assert_eq!(response.mime_type(), "application/json");
+
+        response.headers.clear();
+        assert_eq!(response.mime_type(), DEFAULT_MIME_TYPE);
+        assert_eq!(
+            CustomSchemeResponse::error(404, "missing").mime_type(),
+            "text/plain"
+        );
+    }
+
+    #[test]
+    fn test_response_charset() {
+        let mut response = CustomSchemeResponse {
+            status: 200,
+            headers: vec![(
+                "Content-Type".to_string(),
+                "text/html; Charset=\"ISO-8859-1\"".to_string(),
+            )],
+            body: Vec::new(),
+        };
+        assert_eq!(response.charset(), "ISO-8859-1");
+
+        response.headers[0].1 = "text/html".to_string();
+        assert_eq!(response.charset(), "");
+        response.headers.clear();
+        assert_eq!(response.charset(), "");
+    }
+
+    #[test]
+    fn test_redirect_location() {
+        assert_eq!(
+            CustomSchemeResponse::redirect("http://localhost:5173/@vite/client")
+                .redirect_location(),
+            Some("http://localhost:5173/@vite/client")
+        );
+
+        let mut response = CustomSchemeResponse {
+            status: 200,
+            headers: vec![("location".to_string(), "res://elsewhere".to_string())],
+            body: Vec::new(),
+        };
+        assert_eq!(response.redirect_location(), None);
+        response.status = 302;
+        assert_eq!(response.redirect_location(), Some("res://elsewhere"));
+    }
+}
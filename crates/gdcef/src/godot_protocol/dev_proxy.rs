@@ -0,0 +1,223 @@
+//! Development server proxy for `res://`.
+//!
+//! `godot_cef/schemes/dev_server_routes` maps `res://` prefixes to an upstream
+//! server, one route per line:
+//!
+//! ```text
+//! # <res:// prefix> <upstream URL>
+//! res://ui/ http://localhost:5173/
+//! ```
+//!
+//! In the editor and in debug builds, requests below a prefix are fetched from
+//! the upstream with `cef::Urlrequest`, so pages keep their `res://` URLs while
+//! a dev server such as Vite serves them. Paths matching
+//! `godot_cef/schemes/dev_server_redirect_paths` are redirected instead: the
+//! HMR client then runs from the upstream origin and opens its websocket there.
+//! Release builds ignore the routes and serve the packed files.
+
+use cef::{CefStringUtf16, ImplRequest, Urlrequest};
+use godot::global::godot_warn;
+use std::sync::Arc;
+use url::Url;
+
+use super::custom_scheme::{CustomRequestStateHandle, CustomSchemeResponse, complete_request};
+use super::headers::{glob_matches, url_without_query};
+use super::upstream::start_upstream_request;
+use super::vfs::normalize_mount_prefix;
+use crate::utils::is_development_run;
+
+/// Request headers forwarded to the upstream. Validators are left out on
+/// purpose: a `304` would reach the page without a body.
+const FORWARDED_HEADERS: &[&str] = &["Accept", "Accept-Language", "Content-Type", "Range"];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct DevServerRoute {
+    /// Normalized `res://` prefix ending with `/`.
+    pub prefix: String,
+    /// Upstream base URL ending with `/`.
+    pub upstream: String,
+}
+
+/// Parses the route list. Blank lines, `#` comments and malformed lines are skipped.
+pub(crate) fn parse_dev_server_routes(raw: &str) -> Vec<DevServerRoute> {
+    raw.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let prefix = normalize_mount_prefix(parts.next()?)
+                .filter(|prefix| prefix.starts_with("res://"))?;
+            let upstream = Url::parse(parts.next()?)
+                .ok()
+                .filter(|url| matches!(url.scheme(), "http" | "https"))?;
+            let upstream = upstream.as_str();
+            let upstream = if upstream.ends_with('/') {
+                upstream.to_string()
+            } else {
+                format!("{}/", upstream)
+            };
+            Some(DevServerRoute { prefix, upstream })
+        })
+        .collect()
+}
+
+/// An upstream URL that a `res://` request is answered from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct DevServerTarget {
+    pub url: String,
+    /// Redirect the page to `url` instead of proxying the body.
+    pub redirect: bool,
+}
+
+/// Maps a `res://` request URL onto its upstream URL. The longest prefix wins.
+fn route_request(
+    routes: &[DevServerRoute],
+    redirect_patterns: &[String],
+    url: &str,
+) -> Option<DevServerTarget> {
+    let route = routes
+        .iter()
+        .filter(|route| url.starts_with(route.prefix.as_str()))
+        .max_by_key(|route| route.prefix.len())?;
+
+    let target = format!("{}{}", route.upstream, &url[route.prefix.len()..]);
+    let redirect = redirect_patterns
+        .iter()
+        .any(|pattern| glob_matches(pattern, url_without_query(&target)));
+    Some(DevServerTarget {
+        url: target,
+        redirect,
+    })
+}
+
+/// Returns the upstream that should answer `url`, if a dev server route covers it.
+pub(crate) fn dev_server_target(url: &str) -> Option<DevServerTarget> {
+    let routes = parse_dev_server_routes(&crate::settings::get_dev_server_routes());
+    if routes.is_empty() || !is_development_run() {
+        return None;
+    }
+    route_request(
+        &routes,
+        &crate::settings::get_dev_server_redirect_paths(),
+        url,
+    )
+}
+
+/// Starts fetching `target` on behalf of `request`. The response completes `state`.
+pub(super) fn start_dev_server_request(
+    request: &cef::Request,
+    target: &DevServerTarget,
+    state: &CustomRequestStateHandle,
+) -> Option<Urlrequest> {
+    let Some(mut upstream) = cef::request_create() else {
+        complete_request(
+            state,
+            CustomSchemeResponse::error(500, "Failed to create dev server request"),
+        );
+        return None;
+    };
+    let method = CefStringUtf16::from(&request.method()).to_string();
+    upstream.set_url(Some(&target.url.as_str().into()));
+    upstream.set_method(Some(&method.as_str().into()));
+    for name in FORWARDED_HEADERS {
+        let value =
+            CefStringUtf16::from(&request.header_by_name(Some(&(*name).into()))).to_string();
+        if !value.is_empty() {
+            upstream.set_header_by_name(
+                Some(&(*name).into()),
+                Some(&value.as_str().into()),
+                true as _,
+            );
+        }
+    }
+    if let Some(mut post_data) = request.post_data() {
+        upstream.set_post_data(Some(&mut post_data));
+    }
+
+    let url = target.url.clone();
+    let state = state.clone();
+    start_upstream_request(
+        &mut upstream,
+        None,
+        Arc::new(move |response| {
+            let response = response.unwrap_or_else(|_| {
+                godot_warn!("[GodotScheme] Dev server request failed: {}", url);
+                CustomSchemeResponse::error(502, &format!("Dev server unreachable: {}", url))
+            });
+            complete_request(&state, response);
+        }),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn route(prefix: &str, upstream: &str) -> DevServerRoute {
+        DevServerRoute {
+            prefix: prefix.to_string(),
+            upstream: upstream.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_dev_server_routes() {
+        let routes = parse_dev_server_routes(
+            "# comment\n\
+             res://ui http://localhost:5173\n\
+             res://ui/admin/\thttp://127.0.0.1:5174/admin/\n\
+             user://saves/ http://localhost:5173/\n\
+             res://missing-upstream\n\
+             res://ws/ ws://localhost:5173/\n",
+        );
+        assert_eq!(
+            routes,
+            vec![
+                route("res://ui/", "http://localhost:5173/"),
+                route("res://ui/admin/", "http://127.0.0.1:5174/admin/"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_route_request_prefers_longest_prefix() {
+        let routes = vec![
+            route("res://ui/", "http://localhost:5173/"),
+            route("res://ui/admin/", "http://localhost:5174/"),
+        ];
+
+        assert_eq!(
+            route_request(&routes, &[], "res://ui/src/main.ts?t=1"),
+            Some(DevServerTarget {
+                url: "http://localhost:5173/src/main.ts?t=1".to_string(),
+                redirect: false,
+            })
+        );
+        assert_eq!(
+            route_request(&routes, &[], "res://ui/admin/index.html"),
+            Some(DevServerTarget {
+                url: "http://localhost:5174/index.html".to_string(),
+                redirect: false,
+            })
+        );
+        assert_eq!(route_request(&routes, &[], "res://game/index.html"), None);
+    }
+
+    #[test]
+    fn test_route_request_redirects_hmr_client() {
+        let routes = vec![route("res://ui/", "http://localhost:5173/")];
+        let patterns = vec!["*/@vite/client".to_string()];
+
+        assert_eq!(
+            route_request(&routes, &patterns, "res://ui/@vite/client"),
+            Some(DevServerTarget {
+                url: "http://localhost:5173/@vite/client".to_string(),
+                redirect: true,
+            })
+        );
+        assert!(
+            !route_request(&routes, &patterns, "res://ui/src/App.vue")
+                .is_some_and(|target| target.redirect)
+        );
+    }
+}
@@ -0,0 +1,126 @@
+//! Pre-compressed asset variants.
+//!
+//! Build tools such as Vite can emit `.br` / `.gz` siblings next to each
+//! asset. When the request's `Accept-Encoding` allows it, the sibling is
+//! served with `Content-Encoding` instead of the original file.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ContentEncoding {
+    Brotli,
+    Gzip,
+}
+
+impl ContentEncoding {
+    /// Server preference order: Brotli compresses better, so it wins ties.
+    pub const PREFERENCE: [ContentEncoding; 2] = [ContentEncoding::Brotli, ContentEncoding::Gzip];
+
+    /// File extension of the pre-compressed sibling.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ContentEncoding::Brotli => "br",
+            ContentEncoding::Gzip => "gz",
+        }
+    }
+
+    /// Value for the `Content-Encoding` header.
+    pub fn header_value(&self) -> &'static str {
+        match self {
+            ContentEncoding::Brotli => "br",
+            ContentEncoding::Gzip => "gzip",
+        }
+    }
+
+    pub fn sibling_path(&self, path: &str) -> String {
+        format!("{}.{}", path, self.extension())
+    }
+}
+
+/// Returns the quality value the `Accept-Encoding` header assigns to `coding`.
+///
+/// An explicit entry wins over `*`; codings that are not listed are not acceptable.
+fn accepted_quality(accept_encoding: &str, coding: &str) -> f32 {
+    let mut wildcard = None;
+
+    for entry in accept_encoding.split(',') {
+        let mut params = entry.split(';');
+        let name = params.next().unwrap_or("").trim();
+        let quality = params
+            .filter_map(|param| param.trim().strip_prefix("q="))
+            .find_map(|q| q.trim().parse::<f32>().ok())
+            .unwrap_or(1.0);
+
+        if name.eq_ignore_ascii_case(coding)
+            || (coding == "gzip" && name.eq_ignore_ascii_case("x-gzip"))
+        {
+            return quality;
+        }
+        if name == "*" {
+            wildcard = Some(quality);
+        }
+    }
+
+    wildcard.unwrap_or(0.0)
+}
+
+/// Encodings acceptable for this request, in server preference order.
+pub(crate) fn acceptable_encodings(accept_encoding: &str) -> Vec<ContentEncoding> {
+    if accept_encoding.trim().is_empty() {
+        return Vec::new();
+    }
+
+    ContentEncoding::PREFERENCE
+        .into_iter()
+        .filter(|encoding| accepted_quality(accept_encoding, encoding.header_value()) > 0.0)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_acceptable_encodings_prefers_brotli() {
+        assert_eq!(
+            acceptable_encodings("gzip, deflate, br"),
+            vec![ContentEncoding::Brotli, ContentEncoding::Gzip]
+        );
+        assert_eq!(
+            acceptable_encodings("gzip, deflate"),
+            vec![ContentEncoding::Gzip]
+        );
+        assert_eq!(acceptable_encodings("br"), vec![ContentEncoding::Brotli]);
+    }
+
+    #[test]
+    fn test_acceptable_encodings_empty_or_identity() {
+        assert_eq!(acceptable_encodings(""), vec![]);
+        assert_eq!(acceptable_encodings("identity"), vec![]);
+        assert_eq!(acceptable_encodings("deflate, zstd"), vec![]);
+    }
+
+    #[test]
+    fn test_acceptable_encodings_quality_values() {
+        assert_eq!(
+            acceptable_encodings("br;q=0, gzip;q=0.8"),
+            vec![ContentEncoding::Gzip]
+        );
+        assert_eq!(
+            acceptable_encodings("*;q=0.5, gzip;q=0"),
+            vec![ContentEncoding::Brotli]
+        );
+        assert_eq!(acceptable_encodings("*;q=0"), vec![]);
+        assert_eq!(acceptable_encodings("x-gzip"), vec![ContentEncoding::Gzip]);
+    }
+
+    #[test]
+    fn test_sibling_path() {
+        assert_eq!(
+            ContentEncoding::Brotli.sibling_path("res://ui/app.js"),
+            "res://ui/app.js.br"
+        );
+        assert_eq!(
+            ContentEncoding::Gzip.sibling_path("res://ui/app.js"),
+            "res://ui/app.js.gz"
+        );
+    }
+}
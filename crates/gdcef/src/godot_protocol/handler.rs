@@ -4,24 +4,38 @@
 //! Godot's filesystem in response to `res://` and `user://` URL requests.
 
 use cef::{
-    CefStringUtf16, ImplRequest, ImplResourceHandler, ImplResponse, ImplSchemeHandlerFactory,
-    ResourceHandler, SchemeHandlerFactory, WrapResourceHandler, WrapSchemeHandlerFactory, rc::Rc,
-    wrap_resource_handler, wrap_scheme_handler_factory,
+    CefStringUtf16, ImplBrowser, ImplFrame, ImplRequest, ImplResourceHandler, ImplResponse,
+    ImplSchemeHandlerFactory, ResourceHandler, ResourceType, SchemeHandlerFactory,
+    WrapResourceHandler, WrapSchemeHandlerFactory, rc::Rc, wrap_resource_handler,
+    wrap_scheme_handler_factory,
 };
-use godot::classes::FileAccess;
-use godot::classes::file_access::ModeFlags;
-use godot::prelude::*;
+use godot::global::godot_warn;
 use percent_encoding::percent_decode_str;
 use std::cell::RefCell;
 use std::path::PathBuf;
 use url::Url;
 
 use super::GodotScheme;
+use super::access::{UserAccessPolicy, initiator_origin};
+use super::cache::{if_range_matches, init_pack_stamp, is_not_modified};
+use super::custom_scheme::{
+    CustomSchemeResourceHandler, CustomSchemeResourceHandlerImpl, read_request_body,
+};
+use super::dev_proxy::dev_server_target;
+use super::encoding::{ContentEncoding, acceptable_encodings};
+use super::headers::{HeaderPreset, headers_for, parse_header_rules};
+use super::imported::{ImportedRequest, is_imported_candidate};
+use super::live_reload::record_served_file;
 use super::mime::get_mime_type;
 use super::multipart::{
     MULTIPART_BOUNDARY, MultipartStreamState, read_multipart_streaming, skip_multipart_streaming,
 };
 use super::range::{ParsedRanges, parse_range_header};
+use super::stream::{BodySource, FileStreamState, read_file_streaming};
+use super::vfs::Filesystem;
+use super::write::{
+    UserWritePolicy, WriteMethod, delete_user_file, record_user_file_written, write_user_file,
+};
 
 /// Validate that a string contains only valid percent-encoded sequences.
 ///
@@ -87,6 +101,13 @@ fn contains_path_traversal(decoded_path: &str) -> bool {
 /// not as host + path. We intentionally do NOT use URL normalization to avoid
 /// silently resolving `..` traversal patterns.
 pub(crate) fn parse_godot_url(url_str: &str, scheme: GodotScheme) -> Option<String> {
+    decode_godot_url(url_str, scheme).map(|path| with_index_file(path, scheme))
+}
+
+/// Decodes a URL into a Godot path without resolving directories to `index.html`.
+///
+/// Applies the same traversal and encoding checks as [`parse_godot_url`].
+fn decode_godot_url(url_str: &str, scheme: GodotScheme) -> Option<String> {
     if contains_path_traversal_encoded(url_str) {
         return None;
     }
@@ -104,7 +125,7 @@ pub(crate) fn parse_godot_url(url_str: &str, scheme: GodotScheme) -> Option<Stri
                 // When the URL is of the form `res://file.html?v=1` or `user://data.json#frag`,
                 // `Url::parse` will treat `file.html`/`data.json` as the host and `/` as the path.
                 // In those cases, we must *not* append a trailing slash, otherwise we end up with
-                // `file.html/` which `finalize_godot_path` then treats as a directory.
+                // `file.html/` which `with_index_file` then treats as a directory.
                 if url_path == "/" {
                     // Reconstruct what was after `scheme://` up to any `?` or `#` to see if there
                     // was an explicit trailing slash in the original URL.
@@ -128,10 +149,10 @@ pub(crate) fn parse_godot_url(url_str: &str, scheme: GodotScheme) -> Option<Stri
                 }
             }
         } else {
-            return parse_godot_url_manual(url_str, scheme);
+            return decode_godot_url_manual(url_str, scheme);
         }
     } else {
-        return parse_godot_url_manual(url_str, scheme);
+        return decode_godot_url_manual(url_str, scheme);
     };
 
     if !has_valid_percent_encoding(&path_encoded) {
@@ -151,11 +172,11 @@ pub(crate) fn parse_godot_url(url_str: &str, scheme: GodotScheme) -> Option<Stri
         return None;
     }
 
-    finalize_godot_path(&path, scheme)
+    Some(join_godot_path(&path, scheme))
 }
 
 /// Manual URL parsing fallback for relative paths or when URL crate fails.
-fn parse_godot_url_manual(url_str: &str, scheme: GodotScheme) -> Option<String> {
+fn decode_godot_url_manual(url_str: &str, scheme: GodotScheme) -> Option<String> {
     if contains_path_traversal_encoded(url_str) {
         return None;
     }
@@ -192,14 +213,17 @@ fn parse_godot_url_manual(url_str: &str, scheme: GodotScheme) -> Option<String>
         return None;
     }
 
-    finalize_godot_path(&path, scheme)
+    Some(join_godot_path(&path, scheme))
 }
 
-/// Finalize a decoded path into a full Godot path, adding index.html if needed.
-fn finalize_godot_path(path: &str, scheme: GodotScheme) -> Option<String> {
+/// Joins a decoded path onto the scheme prefix.
+fn join_godot_path(path: &str, scheme: GodotScheme) -> String {
     let path = path.strip_prefix('/').unwrap_or(path);
-    let mut full_path = format!("{}{}", scheme.prefix(), path);
+    format!("{}{}", scheme.prefix(), path)
+}
 
+/// Resolves directory-like paths to their `index.html`.
+fn with_index_file(mut full_path: String, scheme: GodotScheme) -> String {
     // Determine whether the last path component (ignoring trailing '/')
     // has an extension (i.e., contains a dot). This avoids treating dots
     // in parent directory names as file extensions.
@@ -214,7 +238,125 @@ fn finalize_godot_path(path: &str, scheme: GodotScheme) -> Option<String> {
         full_path.push_str("index.html");
     }
 
-    Some(full_path)
+    full_path
+}
+
+const INDEX_FILE: &str = "index.html";
+
+/// Returns the prefix `index.html` that should answer a client-side route, if any.
+///
+/// Only directory-like paths (those resolved to an `index.html`) fall back, so
+/// missing assets such as `app.js` still 404. The longest matching prefix wins.
+fn spa_fallback_index(godot_path: &str, prefixes: &[String]) -> Option<String> {
+    if !godot_path.ends_with(&format!("/{}", INDEX_FILE)) {
+        return None;
+    }
+
+    prefixes
+        .iter()
+        .filter(|prefix| godot_path.starts_with(prefix.as_str()))
+        .max_by_key(|prefix| prefix.len())
+        .map(|prefix| format!("{}{}", prefix, INDEX_FILE))
+}
+
+/// Maps a parsed request path onto the file that should be served.
+///
+/// Handles directories whose names contain dots (`res://v1.2` -> `res://v1.2/index.html`),
+/// extensionless files (`res://LICENSE`), and finally the SPA fallback prefixes.
+fn resolve_served_path(fs: &Filesystem, godot_path: &str) -> Option<String> {
+    if fs.file_exists(godot_path) {
+        return Some(godot_path.to_string());
+    }
+
+    if let Some(stripped) = godot_path.strip_suffix(&format!("/{}", INDEX_FILE)) {
+        if fs.file_exists(stripped) {
+            return Some(stripped.to_string());
+        }
+    } else if fs.dir_exists(godot_path) {
+        let index = format!("{}/{}", godot_path.trim_end_matches('/'), INDEX_FILE);
+        if fs.file_exists(&index) {
+            return Some(index);
+        }
+    }
+
+    spa_fallback_index(godot_path, &crate::settings::get_spa_fallback_prefixes())
+        .filter(|index| fs.file_exists(index))
+}
+
+/// Resolves `godot_path` against the `CefVirtualFs` mounts first, then Godot's
+/// filesystem, and finally the already converted imported resource of a file
+/// that was not exported.
+fn resolve_served_file(godot_path: &str) -> Option<(Filesystem, String)> {
+    Filesystem::mount_for(godot_path)
+        .and_then(|fs| resolve_served_path(&fs, godot_path).map(|path| (fs, path)))
+        .or_else(|| {
+            resolve_served_path(&Filesystem::Godot, godot_path)
+                .map(|path| (Filesystem::Godot, path))
+        })
+        .or_else(|| {
+            Filesystem::Imported
+                .file_exists(godot_path)
+                .then(|| (Filesystem::Imported, godot_path.to_string()))
+        })
+}
+
+/// Returns the path of a `GET` request that can only be answered by converting
+/// an imported resource, which has to happen on the main thread.
+fn unconverted_imported_path(url: &str, method: &str) -> Option<String> {
+    if !method.eq_ignore_ascii_case("GET") {
+        return None;
+    }
+    let godot_path = parse_godot_url(url, GodotScheme::Res)?;
+    (is_imported_candidate(&godot_path) && resolve_served_file(&godot_path).is_none())
+        .then_some(godot_path)
+}
+
+/// Picks the pre-compressed sibling of `godot_path` to serve for this request.
+///
+/// Returns the selected encoding, if any, and whether any sibling exists at all
+/// (the response then varies by `Accept-Encoding`).
+fn select_encoded_variant(
+    fs: &Filesystem,
+    godot_path: &str,
+    accept_encoding: &str,
+) -> (Option<ContentEncoding>, bool) {
+    let available: Vec<ContentEncoding> = ContentEncoding::PREFERENCE
+        .into_iter()
+        .filter(|encoding| fs.file_exists(&encoding.sibling_path(godot_path)))
+        .collect();
+    if available.is_empty() {
+        return (None, false);
+    }
+
+    let selected = acceptable_encodings(accept_encoding)
+        .into_iter()
+        .find(|encoding| available.contains(encoding));
+    (selected, true)
+}
+
+/// Reason phrase for the status codes produced by the scheme handlers.
+pub(super) fn status_text(status_code: i32) -> &'static str {
+    match status_code {
+        200 => "OK",
+        201 => "Created",
+        204 => "No Content",
+        206 => "Partial Content",
+        301 => "Moved Permanently",
+        302 => "Found",
+        304 => "Not Modified",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        307 => "Temporary Redirect",
+        413 => "Content Too Large",
+        416 => "Range Not Satisfiable",
+        500 => "Internal Server Error",
+        502 => "Bad Gateway",
+        507 => "Insufficient Storage",
+        _ => "Unknown",
+    }
 }
 
 #[derive(Clone, Default)]
@@ -230,25 +372,89 @@ struct ResourceState {
     range_end: Option<u64>,
     is_multipart: bool,
     multipart_stream: Option<MultipartStreamState>,
+    file_stream: Option<FileStreamState>,
     file_path: Option<String>,
-    open_file: Option<Gd<FileAccess>>,
+    open_file: Option<BodySource>,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    cache_control: Option<String>,
+    content_encoding: Option<ContentEncoding>,
+    vary_accept_encoding: bool,
+    /// Set for HEAD requests: the `Content-Length` a GET would have returned.
+    head_content_length: Option<i64>,
+    /// Headers from `godot_cef/schemes/response_headers` and the header preset.
+    configured_headers: Vec<(String, String)>,
+}
+
+impl ResourceState {
+    /// Length of the body that `read` will produce.
+    fn body_length(&self) -> i64 {
+        if let Some(ref stream) = self.multipart_stream {
+            stream.total_size as i64
+        } else if let Some(ref stream) = self.file_stream {
+            i64::try_from(stream.length).unwrap_or(i64::MAX)
+        } else {
+            self.data.len() as i64
+        }
+    }
+
+    /// Drops any buffered or streamed body and releases the retained file.
+    fn discard_body(&mut self) {
+        self.multipart_stream = None;
+        self.file_stream = None;
+        self.open_file = None;
+        self.data = Vec::new();
+        self.offset = 0;
+    }
+
+    /// Turns the response into a plain-text error.
+    fn set_error(&mut self, status_code: i32, message: String) {
+        self.status_code = status_code;
+        self.mime_type = "text/plain".to_string();
+        self.response_content_type = "text/plain".to_string();
+        self.data = message.as_bytes().to_vec();
+        self.error_message = Some(message);
+    }
+}
+
+pub(super) fn configured_headers_for(url: &str) -> Vec<(String, String)> {
+    let preset = HeaderPreset::from_setting(crate::settings::get_header_preset());
+    let rules = parse_header_rules(&crate::settings::get_response_header_rules());
+    headers_for(url, preset, &rules)
+}
+
+pub(super) fn cache_control_for(scheme: GodotScheme) -> Option<String> {
+    let value = match scheme {
+        GodotScheme::Res => crate::settings::get_res_cache_control(),
+        GodotScheme::User => crate::settings::get_user_cache_control(),
+    };
+    (!value.is_empty()).then_some(value)
 }
 
 #[derive(Clone)]
 pub struct GodotResourceHandler {
     state: RefCell<ResourceState>,
     scheme: GodotScheme,
+    /// URL of the document that made the request, when known.
+    frame_url: Option<String>,
+    /// Identifier of the browser that made the request, when known.
+    browser_id: Option<i32>,
 }
 
 impl GodotResourceHandler {
-    pub fn new(scheme: GodotScheme) -> Self {
+    pub fn new(scheme: GodotScheme, frame_url: Option<String>, browser_id: Option<i32>) -> Self {
         Self {
             state: RefCell::new(ResourceState::default()),
             scheme,
+            frame_url,
+            browser_id,
         }
     }
 }
 
+/// Methods accepted on `user://`, advertised in CORS preflight responses.
+const USER_ALLOWED_METHODS: &str = "GET, HEAD, PUT, POST, DELETE, OPTIONS";
+
 wrap_resource_handler! {
     pub struct GodotResourceHandlerImpl {
         handler: GodotResourceHandler,
@@ -269,17 +475,17 @@ wrap_resource_handler! {
             let url = CefStringUtf16::from(&url_cef).to_string();
 
             let mut state = self.handler.state.borrow_mut();
+            state.configured_headers = configured_headers_for(&url);
+
+            let header = |name: &str| {
+                CefStringUtf16::from(&request.header_by_name(Some(&name.into()))).to_string()
+            };
 
             // Reject paths with traversal patterns (returns 403 Forbidden)
             let godot_path = match parse_godot_url(&url, self.handler.scheme) {
                 Some(path) => path,
                 None => {
-                    state.status_code = 403;
-                    state.mime_type = "text/plain".to_string();
-                    state.response_content_type = "text/plain".to_string();
-                    let message = "Forbidden: Invalid path".to_string();
-                    state.data = message.as_bytes().to_vec();
-                    state.error_message = Some(message);
+                    state.set_error(403, "Forbidden: Invalid path".to_string());
 
                     if let Some(handle_request) = handle_request {
                         *handle_request = true as _;
@@ -287,15 +493,78 @@ wrap_resource_handler! {
                     return true as _;
                 }
             };
-            let gstring_path = GString::from(&godot_path);
 
-            if !FileAccess::file_exists(&gstring_path) {
-                state.status_code = 404;
+            let method = CefStringUtf16::from(&request.method()).to_string();
+            let write_method = match self.handler.scheme {
+                GodotScheme::User => WriteMethod::parse(&method),
+                GodotScheme::Res => None,
+            };
+            // Writes target the decoded path itself, without the index file reads resolve to
+            let target_path = match write_method {
+                Some(_) => decode_godot_url(&url, self.handler.scheme).unwrap_or_default(),
+                None => godot_path.clone(),
+            };
+
+            // Keep saves and config away from pages that are not allowed to read them
+            if self.handler.scheme == GodotScheme::User {
+                let origin = initiator_origin(&header("Origin"), self.handler.frame_url.as_deref());
+                let is_navigation = matches!(
+                    request.resource_type(),
+                    ResourceType::MAIN_FRAME | ResourceType::SUB_FRAME
+                );
+                if let Err(reason) = UserAccessPolicy::from_settings().check(origin.as_deref(), &target_path, is_navigation) {
+                    godot_warn!("[GodotScheme] Blocked request for {}: {}", url, reason);
+                    state.set_error(403, "Forbidden: Access denied".to_string());
+
+                    if let Some(handle_request) = handle_request {
+                        *handle_request = true as _;
+                    }
+                    return true as _;
+                }
+            }
+
+            // Answer CORS preflights for writes from `res://` pages
+            if self.handler.scheme == GodotScheme::User && method.eq_ignore_ascii_case("OPTIONS") {
+                state.status_code = 204;
                 state.mime_type = "text/plain".to_string();
                 state.response_content_type = "text/plain".to_string();
-                let message = format!("File not found: {}", godot_path);
-                state.data = message.as_bytes().to_vec();
-                state.error_message = Some(message);
+                state.configured_headers.push(("Access-Control-Allow-Methods".to_string(), USER_ALLOWED_METHODS.to_string()));
+                let requested_headers = header("Access-Control-Request-Headers");
+                if !requested_headers.is_empty() {
+                    state.configured_headers.push(("Access-Control-Allow-Headers".to_string(), requested_headers));
+                }
+
+                if let Some(handle_request) = handle_request {
+                    *handle_request = true as _;
+                }
+                return true as _;
+            }
+
+            if let Some(write_method) = write_method {
+                let policy = UserWritePolicy::from_settings();
+                let result = match write_method {
+                    WriteMethod::Put | WriteMethod::Post => {
+                        write_user_file(&policy, &target_path, &read_request_body(request))
+                    }
+                    WriteMethod::Delete => delete_user_file(&policy, &target_path),
+                };
+
+                match result {
+                    Ok(outcome) => {
+                        state.status_code = outcome.status_code();
+                        state.mime_type = "text/plain".to_string();
+                        state.response_content_type = "text/plain".to_string();
+                        if write_method != WriteMethod::Delete
+                            && let Some(browser_id) = self.handler.browser_id
+                        {
+                            record_user_file_written(browser_id, &target_path);
+                        }
+                    }
+                    Err(error) => {
+                        godot_warn!("[GodotScheme] Rejected {} {}: {}", method, url, error);
+                        state.set_error(error.status_code(), format!("{}: {}", status_text(error.status_code()), error));
+                    }
+                }
 
                 if let Some(handle_request) = handle_request {
                     *handle_request = true as _;
@@ -303,12 +572,38 @@ wrap_resource_handler! {
                 return true as _;
             }
 
-            let range_header = request.header_by_name(Some(&"Range".into()));
-            let range_str = CefStringUtf16::from(&range_header).to_string();
+            let Some((fs, godot_path)) = resolve_served_file(&godot_path) else {
+                state.set_error(404, format!("File not found: {}", godot_path));
 
-            match FileAccess::open(&gstring_path, ModeFlags::READ) {
-                Some(mut file) => {
-                    let file_size = file.get_length();
+                if let Some(handle_request) = handle_request {
+                    *handle_request = true as _;
+                }
+                return true as _;
+            };
+            let is_head = method.eq_ignore_ascii_case("HEAD");
+            let is_conditional_method = is_head || method.eq_ignore_ascii_case("GET");
+
+            // Serve a `.br` / `.gz` sibling when the client accepts it. The MIME type
+            // still comes from the original extension below.
+            let (content_encoding, has_encoded_variants) =
+                select_encoded_variant(&fs, &godot_path, &header("Accept-Encoding"));
+            state.content_encoding = content_encoding;
+            state.vary_accept_encoding = has_encoded_variants;
+            let served_path = match content_encoding {
+                Some(encoding) => encoding.sibling_path(&godot_path),
+                None => godot_path.clone(),
+            };
+            match fs.open(&served_path) {
+                Some(opened) => {
+                    if self.handler.scheme == GodotScheme::Res
+                        && matches!(fs, Filesystem::Godot)
+                        && let Some(browser_id) = self.handler.browser_id
+                    {
+                        record_served_file(browser_id, &godot_path);
+                    }
+
+                    let file = opened.body;
+                    let file_size = opened.size;
                     state.total_file_size = file_size;
 
                     let path = PathBuf::from(&godot_path);
@@ -316,9 +611,35 @@ wrap_resource_handler! {
                         .extension()
                         .and_then(|e| e.to_str())
                         .unwrap_or("");
-                    state.mime_type = get_mime_type(extension).to_string();
+                    state.mime_type = opened
+                        .mime_type
+                        .unwrap_or_else(|| get_mime_type(extension))
+                        .to_string();
                     state.response_content_type = state.mime_type.clone();
 
+                    let validators = opened.validators;
+                    state.etag = validators.etag.clone();
+                    state.last_modified = validators.last_modified();
+                    state.cache_control = cache_control_for(self.handler.scheme);
+
+                    let not_modified = is_conditional_method
+                        && is_not_modified(
+                            &header("If-None-Match"),
+                            &header("If-Modified-Since"),
+                            &validators,
+                        );
+
+                    // A stale `If-Range` validator means the full representation is sent.
+                    // Ranges are not offered on encoded bodies.
+                    let range_str = if not_modified
+                        || content_encoding.is_some()
+                        || !if_range_matches(&header("If-Range"), &validators)
+                    {
+                        String::new()
+                    } else {
+                        header("Range")
+                    };
+
                     // Parse `Range` header. Supports single ranges ("bytes=start-end",
                     // "bytes=start-", "bytes=-suffix_length") and multi-range requests
                     // ("bytes=0-100,200-300").
@@ -331,15 +652,15 @@ wrap_resource_handler! {
                                 state.range_end = None;
                                 state.is_multipart = false;
                             } else {
-                                let content_size_u64 = range.end.saturating_sub(range.start).saturating_add(1);
-                                let content_size = i64::try_from(content_size_u64).unwrap_or(i64::MAX);
-                                file.seek(range.start);
-                                let buffer = file.get_buffer(content_size);
-                                state.data = buffer.as_slice().to_vec();
+                                // Body is streamed from the retained file in `read`
+                                let content_size = range.end.saturating_sub(range.start).saturating_add(1);
                                 state.status_code = 206;
                                 state.range_start = Some(range.start);
                                 state.range_end = Some(range.end);
                                 state.is_multipart = false;
+                                state.file_stream = Some(FileStreamState::new(range.start, content_size));
+                                state.open_file = Some(file);
+                                state.data = Vec::new();
                                 state.offset = 0;
                             }
                         }
@@ -358,30 +679,38 @@ wrap_resource_handler! {
                             state.range_start = None;
                             state.range_end = None;
                             state.is_multipart = true;
-                            state.file_path = Some(godot_path.clone());
+                            state.file_path = Some(served_path.clone());
                             state.multipart_stream = Some(stream_state);
+                            state.open_file = Some(file);
                             state.data = Vec::new(); // Data will be streamed, not buffered
                             state.offset = 0;
                         }
                         None => {
-                            let buffer_size = i64::try_from(file_size).unwrap_or(i64::MAX);
-                            let buffer = file.get_buffer(buffer_size);
-                            state.data = buffer.as_slice().to_vec();
+                            // Body is streamed from the retained file in `read`
                             state.status_code = 200;
                             state.range_start = None;
                             state.range_end = None;
                             state.is_multipart = false;
+                            state.file_stream = Some(FileStreamState::new(0, file_size));
+                            state.open_file = Some(file);
+                            state.data = Vec::new();
                             state.offset = 0;
                         }
                     }
+
+                    if not_modified {
+                        state.status_code = 304;
+                        state.discard_body();
+                    }
+
+                    if is_head {
+                        state.head_content_length = Some(state.body_length());
+                        state.discard_body();
+                    }
                 }
                 None => {
-                    state.status_code = 500;
-                    state.mime_type = "text/plain".to_string();
-                    state.response_content_type = "text/plain".to_string();
-                    let message = format!("Failed to open file: {}", godot_path);
-                    state.data = message.as_bytes().to_vec();
-                    state.error_message = Some(message);
+                    state.content_encoding = None;
+                    state.set_error(500, format!("Failed to open file: {}", served_path));
                 }
             }
 
@@ -403,22 +732,39 @@ wrap_resource_handler! {
             if let Some(response) = response {
                 response.set_status(state.status_code);
 
-                let status_text = match state.status_code {
-                    200 => "OK",
-                    206 => "Partial Content",
-                    403 => "Forbidden",
-                    404 => "Not Found",
-                    416 => "Range Not Satisfiable",
-                    500 => "Internal Server Error",
-                    _ => "Unknown",
-                };
+                let status_text = status_text(state.status_code);
                 response.set_status_text(Some(&status_text.into()));
 
                 response.set_mime_type(Some(&state.response_content_type.as_str().into()));
 
                 response.set_header_by_name(Some(&"Content-Type".into()), Some(&state.response_content_type.as_str().into()), true as _);
-                response.set_header_by_name(Some(&"Access-Control-Allow-Origin".into()), Some(&"*".into()), true as _);
-                response.set_header_by_name(Some(&"Accept-Ranges".into()), Some(&"bytes".into()), true as _);
+                let accept_ranges = if state.content_encoding.is_some() { "none" } else { "bytes" };
+                response.set_header_by_name(Some(&"Accept-Ranges".into()), Some(&accept_ranges.into()), true as _);
+
+                if let Some(encoding) = state.content_encoding {
+                    response.set_header_by_name(Some(&"Content-Encoding".into()), Some(&encoding.header_value().into()), true as _);
+                }
+                if state.vary_accept_encoding {
+                    response.set_header_by_name(Some(&"Vary".into()), Some(&"Accept-Encoding".into()), true as _);
+                }
+
+                if let Some(ref etag) = state.etag {
+                    response.set_header_by_name(Some(&"ETag".into()), Some(&etag.as_str().into()), true as _);
+                }
+                if let Some(ref last_modified) = state.last_modified {
+                    response.set_header_by_name(Some(&"Last-Modified".into()), Some(&last_modified.as_str().into()), true as _);
+                }
+                if let Some(ref cache_control) = state.cache_control {
+                    response.set_header_by_name(Some(&"Cache-Control".into()), Some(&cache_control.as_str().into()), true as _);
+                }
+                if let Some(content_length) = state.head_content_length {
+                    let value: CefStringUtf16 = content_length.to_string().as_str().into();
+                    response.set_header_by_name(Some(&"Content-Length".into()), Some(&value), true as _);
+                }
+
+                for (name, value) in &state.configured_headers {
+                    response.set_header_by_name(Some(&name.as_str().into()), Some(&value.as_str().into()), true as _);
+                }
 
                 if state.status_code == 206 && !state.is_multipart {
                     if let (Some(start), Some(end)) = (state.range_start, state.range_end) {
@@ -432,12 +778,12 @@ wrap_resource_handler! {
             }
 
             if let Some(response_length) = response_length {
-                // For streaming multipart responses, use pre-calculated total size
-                if let Some(ref stream) = state.multipart_stream {
-                    *response_length = stream.total_size as i64;
+                // HEAD responses carry no body; streamed bodies use their pre-calculated size
+                *response_length = if state.head_content_length.is_some() {
+                    0
                 } else {
-                    *response_length = state.data.len() as i64;
-                }
+                    state.body_length()
+                };
             }
         }
 
@@ -497,6 +843,26 @@ wrap_resource_handler! {
                 return (written > 0) as _;
             }
 
+            // Handle streaming single-file responses (200 and single-range 206)
+            if state.file_stream.is_some() {
+                let ResourceState {
+                    file_stream,
+                    open_file,
+                    ..
+                } = &mut *state;
+
+                let written = match file_stream.as_mut() {
+                    Some(stream) => read_file_streaming(stream, open_file, data_out, bytes_to_read),
+                    None => 0,
+                };
+
+                if let Some(bytes_read) = bytes_read {
+                    *bytes_read = written as _;
+                }
+
+                return (written > 0) as _;
+            }
+
             // Handle buffered (non-streaming) responses
             let remaining = state.data.len().saturating_sub(state.offset);
 
@@ -561,6 +927,16 @@ wrap_resource_handler! {
                 return true as _;
             }
 
+            if let Some(stream) = state.file_stream.as_mut() {
+                let skipped = stream.skip(bytes_to_skip);
+
+                if let Some(bytes_skipped) = bytes_skipped {
+                    *bytes_skipped = skipped as i64;
+                }
+
+                return true as _;
+            }
+
             // Handle buffered (non-streaming) responses
             let remaining = state.data.len().saturating_sub(state.offset);
             let to_skip = remaining.min(bytes_to_skip);
@@ -577,11 +953,8 @@ wrap_resource_handler! {
         fn cancel(&self) {
             let mut state = self.handler.state.borrow_mut();
 
-            // If a multipart stream is active, explicitly release its resources
-            if state.multipart_stream.is_some() {
-                state.multipart_stream = None;
-                state.open_file = None;
-            }
+            // Release any active stream and the retained file handle
+            state.discard_body();
         }
     }
 }
@@ -611,12 +984,29 @@ wrap_scheme_handler_factory! {
     impl SchemeHandlerFactory {
         fn create(
             &self,
-            _browser: Option<&mut cef::Browser>,
-            _frame: Option<&mut cef::Frame>,
+            browser: Option<&mut cef::Browser>,
+            frame: Option<&mut cef::Frame>,
             _scheme_name: Option<&cef::CefString>,
-            _request: Option<&mut cef::Request>,
+            request: Option<&mut cef::Request>,
         ) -> Option<ResourceHandler> {
-            Some(GodotResourceHandlerImpl::build(GodotResourceHandler::new(self.handler.scheme)))
+            if self.handler.scheme == GodotScheme::Res
+                && let Some(request) = request
+            {
+                let url = CefStringUtf16::from(&request.url()).to_string();
+                if let Some(target) = dev_server_target(&url) {
+                    return Some(CustomSchemeResourceHandlerImpl::build(CustomSchemeResourceHandler::for_dev_server(target)));
+                }
+                let method = CefStringUtf16::from(&request.method()).to_string();
+                if let Some(path) = unconverted_imported_path(&url, &method) {
+                    let if_none_match = CefStringUtf16::from(&request.header_by_name(Some(&"If-None-Match".into()))).to_string();
+                    let imported = ImportedRequest { path, url, if_none_match };
+                    return Some(CustomSchemeResourceHandlerImpl::build(CustomSchemeResourceHandler::for_imported_resource(imported)));
+                }
+            }
+
+            let frame_url = frame.map(|frame| CefStringUtf16::from(&frame.url()).to_string());
+            let browser_id = browser.map(|browser| browser.identifier());
+            Some(GodotResourceHandlerImpl::build(GodotResourceHandler::new(self.handler.scheme, frame_url, browser_id)))
         }
     }
 }
@@ -629,6 +1019,7 @@ impl GodotSchemeHandlerFactory {
 
 fn register_scheme_handler_on_context(context: &mut cef::RequestContext, scheme: GodotScheme) {
     use cef::ImplRequestContext;
+    init_pack_stamp();
     let mut factory = GodotSchemeHandlerFactory::build(GodotSchemeHandler::new(scheme));
     context.register_scheme_handler_factory(
         Some(&scheme.name().into()),
@@ -841,6 +1232,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_spa_fallback_index() {
+        let prefixes = vec!["res://ui/".to_string(), "res://ui/admin/".to_string()];
+
+        assert_eq!(
+            spa_fallback_index("res://ui/users/42/index.html", &prefixes),
+            Some("res://ui/index.html".to_string())
+        );
+        assert_eq!(
+            spa_fallback_index("res://ui/admin/settings/index.html", &prefixes),
+            Some("res://ui/admin/index.html".to_string())
+        );
+        // Missing assets are not routes
+        assert_eq!(spa_fallback_index("res://ui/app.js", &prefixes), None);
+        // Paths outside every prefix
+        assert_eq!(
+            spa_fallback_index("res://other/page/index.html", &prefixes),
+            None
+        );
+        assert_eq!(spa_fallback_index("res://ui/page/index.html", &[]), None);
+    }
+
     #[test]
     fn test_has_valid_percent_encoding() {
         // Valid encodings
@@ -0,0 +1,240 @@
+//! Configurable response headers for `res://` and `user://`.
+//!
+//! Rules come from `godot_cef/schemes/response_headers`, one per line:
+//!
+//! ```text
+//! # <url-pattern> <Header-Name>: <value>
+//! *                Access-Control-Allow-Origin: *
+//! res://ui/*       Content-Security-Policy: default-src 'self'
+//! user://*         Access-Control-Allow-Origin:
+//! ```
+//!
+//! `*` in a pattern matches any run of characters. Rules apply in order, so a
+//! later rule replaces an earlier header of the same name, and an empty value
+//! removes it.
+
+/// Built-in header sets selectable through `godot_cef/schemes/header_preset`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum HeaderPreset {
+    None,
+    /// `Cross-Origin-Opener-Policy` / `Cross-Origin-Embedder-Policy`, required for
+    /// `SharedArrayBuffer` and therefore threaded WASM (Emscripten, Godot web exports).
+    CrossOriginIsolation,
+}
+
+impl HeaderPreset {
+    pub fn from_setting(value: i64) -> Self {
+        match value {
+            1 => HeaderPreset::CrossOriginIsolation,
+            _ => HeaderPreset::None,
+        }
+    }
+
+    fn headers(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            HeaderPreset::None => &[],
+            HeaderPreset::CrossOriginIsolation => &[
+                ("Cross-Origin-Opener-Policy", "same-origin"),
+                ("Cross-Origin-Embedder-Policy", "require-corp"),
+            ],
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct HeaderRule {
+    pub pattern: String,
+    pub name: String,
+    pub value: String,
+}
+
+fn is_valid_header_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b))
+}
+
+/// Parses the rule list. Blank lines, `#` comments and malformed lines are skipped.
+pub(crate) fn parse_header_rules(raw: &str) -> Vec<HeaderRule> {
+    raw.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let (pattern, header) = line.split_once(char::is_whitespace)?;
+            let (name, value) = header.split_once(':')?;
+            let name = name.trim();
+            if !is_valid_header_name(name) {
+                return None;
+            }
+            Some(HeaderRule {
+                pattern: pattern.to_string(),
+                name: name.to_string(),
+                value: value.trim().to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Matches `text` against a pattern where `*` stands for any run of characters.
+pub(crate) fn glob_matches(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let Some(first) = parts.next() else {
+        return text.is_empty();
+    };
+    let Some(mut rest) = text.strip_prefix(first) else {
+        return false;
+    };
+
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        // No wildcard: the pattern must match exactly
+        return rest.is_empty();
+    };
+
+    for part in middle {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+/// Strips the query string and fragment from a request URL.
+pub(crate) fn url_without_query(url: &str) -> &str {
+    let end = url.find(['?', '#']).unwrap_or(url.len());
+    &url[..end]
+}
+
+/// Resolves the headers to add to the response for `url`, in application order.
+pub(crate) fn headers_for(
+    url: &str,
+    preset: HeaderPreset,
+    rules: &[HeaderRule],
+) -> Vec<(String, String)> {
+    let url = url_without_query(url);
+    let mut headers: Vec<(String, String)> = Vec::new();
+
+    let mut apply = |name: &str, value: &str| {
+        headers.retain(|(existing, _)| !existing.eq_ignore_ascii_case(name));
+        if !value.is_empty() {
+            headers.push((name.to_string(), value.to_string()));
+        }
+    };
+
+    for (name, value) in preset.headers() {
+        apply(name, value);
+    }
+    for rule in rules.iter().filter(|rule| glob_matches(&rule.pattern, url)) {
+        apply(&rule.name, &rule.value);
+    }
+
+    headers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(pattern: &str, name: &str, value: &str) -> HeaderRule {
+        HeaderRule {
+            pattern: pattern.to_string(),
+            name: name.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_header_rules() {
+        let rules = parse_header_rules(
+            "# comment\n\
+             *   Access-Control-Allow-Origin: *\n\
+             res://ui/*\tContent-Security-Policy: default-src 'self'; img-src data:\n\
+             user://* Access-Control-Allow-Origin:\n\
+             missing-colon X-Header\n\
+             res://* Bad Header: value\n",
+        );
+        assert_eq!(
+            rules,
+            vec![
+                rule("*", "Access-Control-Allow-Origin", "*"),
+                rule(
+                    "res://ui/*",
+                    "Content-Security-Policy",
+                    "default-src 'self'; img-src data:"
+                ),
+                rule("user://*", "Access-Control-Allow-Origin", ""),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_glob_matches() {
+        assert!(glob_matches("*", "res://index.html"));
+        assert!(glob_matches("res://*", "res://ui/app.js"));
+        assert!(!glob_matches("res://*", "user://save.json"));
+        assert!(glob_matches("res://ui/*.wasm", "res://ui/game.wasm"));
+        assert!(!glob_matches("res://ui/*.wasm", "res://ui/game.wasm.map"));
+        assert!(glob_matches("*/game/*", "res://web/game/index.html"));
+        assert!(glob_matches("res://index.html", "res://index.html"));
+        assert!(!glob_matches("res://index.html", "res://index.html.br"));
+    }
+
+    #[test]
+    fn test_headers_for_applies_in_order() {
+        let rules = vec![
+            rule("*", "Access-Control-Allow-Origin", "*"),
+            rule("user://*", "access-control-allow-origin", ""),
+            rule(
+                "res://ui/*",
+                "Content-Security-Policy",
+                "default-src 'self'",
+            ),
+        ];
+
+        assert_eq!(
+            headers_for("res://ui/index.html?v=2", HeaderPreset::None, &rules),
+            vec![
+                ("Access-Control-Allow-Origin".to_string(), "*".to_string()),
+                (
+                    "Content-Security-Policy".to_string(),
+                    "default-src 'self'".to_string()
+                ),
+            ]
+        );
+        assert_eq!(
+            headers_for("user://save.json", HeaderPreset::None, &rules),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn test_cross_origin_isolation_preset() {
+        let headers = headers_for(
+            "res://game/index.html",
+            HeaderPreset::CrossOriginIsolation,
+            &[],
+        );
+        assert!(headers.contains(&(
+            "Cross-Origin-Opener-Policy".to_string(),
+            "same-origin".to_string()
+        )));
+        assert!(headers.contains(&(
+            "Cross-Origin-Embedder-Policy".to_string(),
+            "require-corp".to_string()
+        )));
+
+        // Rules can override preset headers
+        let rules = vec![rule("*", "Cross-Origin-Embedder-Policy", "credentialless")];
+        let headers = headers_for(
+            "res://index.html",
+            HeaderPreset::CrossOriginIsolation,
+            &rules,
+        );
+        assert!(headers.contains(&(
+            "Cross-Origin-Embedder-Policy".to_string(),
+            "credentialless".to_string()
+        )));
+    }
+}
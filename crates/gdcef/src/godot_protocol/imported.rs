@@ -0,0 +1,396 @@
+//! Fallback for `res://` files that only exist as imported resources.
+//!
+//! Exporting a project replaces many source assets with their imported form
+//! (`logo.png` becomes a `.ctex`, `theme.ttf` a `.fontdata`), so the raw file
+//! a page asks for is missing from the `.pck`. When that happens the request is
+//! answered by the deferred handler of [`super::custom_scheme`]: the main
+//! thread loads the path through `ResourceLoader` and re-encodes it:
+//!
+//! - textures are saved as PNG, or as WebP/JPEG when the request asked for one
+//! - `AudioStreamMP3` serves its original bytes
+//! - 8/16-bit `AudioStreamWAV` is wrapped in a RIFF header
+//! - `FontFile` serves its original font data
+//!
+//! Ogg Vorbis and compressed WAV streams cannot be re-encoded and still 404.
+//! Converted responses carry an `ETag` and answer a matching `If-None-Match`
+//! with `304`.
+//! Results are kept in memory up to `godot_cef/schemes/imported_resource_cache_mb`,
+//! and cached results are served by the `res://` handler directly.
+
+use godot::classes::audio_stream_wav::Format as WavFormat;
+use godot::classes::{AudioStreamMp3, AudioStreamWav, FontFile, Os, ResourceLoader, Texture2D};
+use godot::global::{Error, godot_warn};
+use godot::prelude::*;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, LazyLock, Mutex};
+
+use super::GodotScheme;
+use super::cache::etag_matches_any;
+use super::custom_scheme::{
+    CustomRequestStateHandle, CustomSchemeResponse, complete_request, is_request_canceled,
+};
+use super::handler::{cache_control_for, configured_headers_for};
+use super::mime::get_mime_type;
+use super::vfs::memory_etag;
+
+const BYTES_PER_MB: u64 = 1024 * 1024;
+
+const TEXTURE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "webp", "svg", "bmp", "tga"];
+const AUDIO_EXTENSIONS: &[&str] = &["mp3", "wav"];
+const FONT_EXTENSIONS: &[&str] = &["ttf", "otf", "woff", "woff2"];
+
+/// A resource re-encoded into a format the browser understands.
+pub(crate) struct ConvertedResource {
+    pub data: Arc<[u8]>,
+    pub mime_type: &'static str,
+    pub etag: String,
+}
+
+#[derive(Default)]
+struct ConvertedCache {
+    entries: HashMap<String, Arc<ConvertedResource>>,
+    /// Insertion order, oldest first, for eviction.
+    order: VecDeque<String>,
+    bytes: u64,
+    /// Paths that could not be converted. Only filled in exported builds, where
+    /// packed resources cannot change; editor runs retry after an asset is fixed.
+    failed: HashSet<String>,
+}
+
+impl ConvertedCache {
+    fn insert(&mut self, path: String, resource: Arc<ConvertedResource>, budget: u64) {
+        let size = resource.data.len() as u64;
+        if size > budget {
+            return;
+        }
+        while self.bytes + size > budget {
+            let Some(oldest) = self.order.pop_front() else {
+                break;
+            };
+            if let Some(evicted) = self.entries.remove(&oldest) {
+                self.bytes -= evicted.data.len() as u64;
+            }
+        }
+        if let Some(replaced) = self.entries.insert(path.clone(), resource) {
+            self.bytes -= replaced.data.len() as u64;
+            self.order.retain(|existing| *existing != path);
+        }
+        self.order.push_back(path);
+        self.bytes += size;
+    }
+}
+
+static CACHE: LazyLock<Mutex<ConvertedCache>> =
+    LazyLock::new(|| Mutex::new(ConvertedCache::default()));
+
+/// A `res://` request waiting for its resource to be converted on the main thread.
+#[derive(Clone, Debug)]
+pub(crate) struct ImportedRequest {
+    pub path: String,
+    pub url: String,
+    /// The request's `If-None-Match` header, empty if it sent none.
+    pub if_none_match: String,
+}
+
+struct PendingConversion {
+    request: ImportedRequest,
+    state: CustomRequestStateHandle,
+}
+
+/// Conversions queued by the IO thread, run by `process_pending_conversions`.
+static PENDING_CONVERSIONS: Mutex<VecDeque<PendingConversion>> = Mutex::new(VecDeque::new());
+
+fn extension_of(path: &str) -> Option<String> {
+    let file_name = path.rsplit('/').next()?;
+    let (_, extension) = file_name.rsplit_once('.')?;
+    Some(extension.to_ascii_lowercase())
+}
+
+/// Whether `path` has an extension this module knows how to re-encode.
+fn is_convertible(path: &str) -> bool {
+    extension_of(path).is_some_and(|extension| {
+        [TEXTURE_EXTENSIONS, AUDIO_EXTENSIONS, FONT_EXTENSIONS]
+            .iter()
+            .any(|list| list.contains(&extension.as_str()))
+    })
+}
+
+/// Builds a WAV file around raw little-endian PCM samples.
+fn wav_file(pcm: &[u8], channels: u16, sample_rate: u32, bits_per_sample: u16) -> Vec<u8> {
+    let block_align = channels * bits_per_sample / 8;
+    let byte_rate = sample_rate * u32::from(block_align);
+    let data_len = pcm.len() as u32;
+
+    let mut out = Vec::with_capacity(44 + pcm.len());
+    out.extend_from_slice(b"RIFF");
+    out.extend_from_slice(&(36 + data_len).to_le_bytes());
+    out.extend_from_slice(b"WAVEfmt ");
+    out.extend_from_slice(&16u32.to_le_bytes());
+    out.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    out.extend_from_slice(&channels.to_le_bytes());
+    out.extend_from_slice(&sample_rate.to_le_bytes());
+    out.extend_from_slice(&byte_rate.to_le_bytes());
+    out.extend_from_slice(&block_align.to_le_bytes());
+    out.extend_from_slice(&bits_per_sample.to_le_bytes());
+    out.extend_from_slice(b"data");
+    out.extend_from_slice(&data_len.to_le_bytes());
+    out.extend_from_slice(pcm);
+    out
+}
+
+fn encode_texture(texture: Gd<Texture2D>, extension: &str) -> Option<(Vec<u8>, &'static str)> {
+    let mut image = texture.get_image()?;
+    if image.is_compressed() && image.decompress() != Error::OK {
+        return None;
+    }
+
+    let (buffer, mime_type) = match extension {
+        "webp" => (image.save_webp_to_buffer(), "image/webp"),
+        "jpg" | "jpeg" => (image.save_jpg_to_buffer(), "image/jpeg"),
+        // SVG, BMP and TGA are rasterized at import time, so PNG is the closest match
+        _ => (image.save_png_to_buffer(), "image/png"),
+    };
+    (!buffer.is_empty()).then(|| (buffer.to_vec(), mime_type))
+}
+
+fn encode_wav(stream: Gd<AudioStreamWav>) -> Option<Vec<u8>> {
+    let channels = if stream.is_stereo() { 2 } else { 1 };
+    let sample_rate = u32::try_from(stream.get_mix_rate()).ok()?;
+    let data = stream.get_data();
+    match stream.get_format() {
+        // Godot stores 8-bit samples signed, WAV expects them unsigned
+        WavFormat::FORMAT_8_BITS => {
+            let pcm: Vec<u8> = data.as_slice().iter().map(|b| b ^ 0x80).collect();
+            Some(wav_file(&pcm, channels, sample_rate, 8))
+        }
+        WavFormat::FORMAT_16_BITS => Some(wav_file(data.as_slice(), channels, sample_rate, 16)),
+        _ => None,
+    }
+}
+
+fn convert(path: &str) -> Option<ConvertedResource> {
+    let extension = extension_of(path)?;
+    let gstring_path = GString::from(path);
+    let mut loader = ResourceLoader::singleton();
+    if !loader.exists(&gstring_path) {
+        return None;
+    }
+    let resource = loader.load(&gstring_path)?;
+
+    let (data, mime_type) = if TEXTURE_EXTENSIONS.contains(&extension.as_str()) {
+        encode_texture(resource.try_cast::<Texture2D>().ok()?, &extension)?
+    } else if extension == "mp3" {
+        let data = resource.try_cast::<AudioStreamMp3>().ok()?.get_data();
+        (data.to_vec(), "audio/mpeg")
+    } else if extension == "wav" {
+        (
+            encode_wav(resource.try_cast::<AudioStreamWav>().ok()?)?,
+            "audio/wav",
+        )
+    } else {
+        let data = resource.try_cast::<FontFile>().ok()?.get_data();
+        (data.to_vec(), get_mime_type(&extension))
+    };
+    if data.is_empty() {
+        return None;
+    }
+
+    let data: Arc<[u8]> = Arc::from(data);
+    let etag = memory_etag(&data);
+    Some(ConvertedResource {
+        data,
+        mime_type,
+        etag,
+    })
+}
+
+/// Whether a missing `path` may be served from its imported resource.
+pub(crate) fn is_imported_candidate(path: &str) -> bool {
+    path.starts_with("res://")
+        && is_convertible(path)
+        && crate::settings::is_imported_resource_fallback_enabled()
+}
+
+/// Returns `path` if it was already converted. Safe to call from any thread.
+pub(crate) fn cached_resource(path: &str) -> Option<Arc<ConvertedResource>> {
+    if !is_imported_candidate(path) {
+        return None;
+    }
+    let cache = CACHE.lock().ok()?;
+    cache.entries.get(path).map(Arc::clone)
+}
+
+/// Converts `path` and caches the result. Must be called on the main thread.
+fn convert_and_cache(path: &str) -> Option<Arc<ConvertedResource>> {
+    if let Some(resource) = cached_resource(path) {
+        return Some(resource);
+    }
+    if CACHE.lock().ok()?.failed.contains(path) {
+        return None;
+    }
+
+    // Converting can take a while, so the cache is not held meanwhile
+    let converted = convert(path).map(Arc::new);
+    let mut cache = CACHE.lock().ok()?;
+    match &converted {
+        Some(resource) => {
+            let budget = crate::settings::get_imported_resource_cache_mb() * BYTES_PER_MB;
+            cache.insert(path.to_string(), Arc::clone(resource), budget);
+        }
+        None => {
+            if ResourceLoader::singleton().exists(&GString::from(path)) {
+                godot_warn!(
+                    "[GodotScheme] {} is an imported resource that cannot be re-encoded",
+                    path
+                );
+            }
+            if Os::singleton().has_feature("template") {
+                cache.failed.insert(path.to_string());
+            }
+        }
+    }
+    converted
+}
+
+/// Whether a request sending `if_none_match` already holds `resource`.
+fn is_not_modified(resource: &ConvertedResource, if_none_match: &str) -> bool {
+    !if_none_match.trim().is_empty() && etag_matches_any(if_none_match, &resource.etag)
+}
+
+fn converted_response(
+    resource: &ConvertedResource,
+    request: &ImportedRequest,
+) -> CustomSchemeResponse {
+    let mut headers = vec![
+        ("Content-Type".to_string(), resource.mime_type.to_string()),
+        ("ETag".to_string(), resource.etag.clone()),
+    ];
+    if let Some(cache_control) = cache_control_for(GodotScheme::Res) {
+        headers.push(("Cache-Control".to_string(), cache_control));
+    }
+    headers.extend(configured_headers_for(&request.url));
+
+    if is_not_modified(resource, &request.if_none_match) {
+        return CustomSchemeResponse {
+            status: 304,
+            headers,
+            body: Vec::new(),
+        };
+    }
+    CustomSchemeResponse {
+        status: 200,
+        headers,
+        body: resource.data.to_vec(),
+    }
+}
+
+/// Queues `request` for conversion; `state` is completed on the main thread.
+pub(super) fn queue_conversion(request: ImportedRequest, state: CustomRequestStateHandle) {
+    if let Ok(mut queue) = PENDING_CONVERSIONS.lock() {
+        queue.push_back(PendingConversion { request, state });
+    }
+}
+
+/// Converts the queued imported resources and completes their requests.
+/// Must be called on the main thread.
+pub(super) fn process_pending_conversions() {
+    let pending: Vec<PendingConversion> = match PENDING_CONVERSIONS.lock() {
+        Ok(mut queue) => queue.drain(..).collect(),
+        Err(_) => return,
+    };
+
+    for conversion in pending {
+        if is_request_canceled(&conversion.state) {
+            continue;
+        }
+        let request = &conversion.request;
+        let response = match convert_and_cache(&request.path) {
+            Some(resource) => converted_response(&resource, request),
+            None => CustomSchemeResponse::error(404, &format!("File not found: {}", request.path)),
+        };
+        complete_request(&conversion.state, response);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn converted(len: usize) -> Arc<ConvertedResource> {
+        Arc::new(ConvertedResource {
+            data: Arc::from(vec![0u8; len]),
+            mime_type: "image/png",
+            etag: String::new(),
+        })
+    }
+
+    #[test]
+    fn test_is_not_modified() {
+        let data: Arc<[u8]> = Arc::from(vec![1u8, 2, 3]);
+        let etag = memory_etag(&data);
+        let resource = ConvertedResource {
+            data,
+            mime_type: "image/png",
+            etag: etag.clone(),
+        };
+
+        assert!(is_not_modified(&resource, &etag));
+        assert!(is_not_modified(
+            &resource,
+            &format!("\"other\", W/{}", etag)
+        ));
+        assert!(!is_not_modified(&resource, "\"other\""));
+        assert!(!is_not_modified(&resource, ""));
+    }
+
+    #[test]
+    fn test_is_convertible() {
+        assert!(is_convertible("res://ui/logo.png"));
+        assert!(is_convertible("res://ui/Logo.SVG"));
+        assert!(is_convertible("res://fonts/inter.woff2"));
+        assert!(is_convertible("res://sfx/click.wav"));
+        assert!(!is_convertible("res://music/theme.ogg"));
+        assert!(!is_convertible("res://ui/app.js"));
+        assert!(!is_convertible("res://ui/logo.png.br"));
+        assert!(!is_convertible("res://ui.v2/LICENSE"));
+    }
+
+    #[test]
+    fn test_wav_file_header() {
+        let wav = wav_file(&[1, 2, 3, 4], 2, 44100, 16);
+        assert_eq!(wav.len(), 48);
+        assert_eq!(&wav[0..4], b"RIFF");
+        assert_eq!(u32::from_le_bytes([wav[4], wav[5], wav[6], wav[7]]), 40);
+        assert_eq!(&wav[8..16], b"WAVEfmt ");
+        assert_eq!(u16::from_le_bytes([wav[22], wav[23]]), 2);
+        assert_eq!(
+            u32::from_le_bytes([wav[24], wav[25], wav[26], wav[27]]),
+            44100
+        );
+        assert_eq!(
+            u32::from_le_bytes([wav[28], wav[29], wav[30], wav[31]]),
+            44100 * 4
+        );
+        assert_eq!(u16::from_le_bytes([wav[32], wav[33]]), 4);
+        assert_eq!(&wav[36..40], b"data");
+        assert_eq!(&wav[44..], &[1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_cache_evicts_oldest_entries() {
+        let mut cache = ConvertedCache::default();
+        cache.insert("res://a.png".to_string(), converted(40), 100);
+        cache.insert("res://b.png".to_string(), converted(40), 100);
+        cache.insert("res://c.png".to_string(), converted(40), 100);
+
+        assert!(!cache.entries.contains_key("res://a.png"));
+        assert!(cache.entries.contains_key("res://b.png"));
+        assert!(cache.entries.contains_key("res://c.png"));
+        assert_eq!(cache.bytes, 80);
+
+        // Entries larger than the whole budget are not cached
+        cache.insert("res://huge.png".to_string(), converted(101), 100);
+        assert!(!cache.entries.contains_key("res://huge.png"));
+        assert_eq!(cache.bytes, 80);
+    }
+}
@@ -0,0 +1,74 @@
+//! Tracks which `res://` files were served to browsers with `live_reload` on.
+//!
+//! The scheme handler records every file it opens from Godot's filesystem for
+//! browsers that registered here; `CefTexture` takes the new paths each frame
+//! and polls their modification times.
+
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+
+/// Newly served paths by browser identifier, for watched browsers only.
+static SERVED_FILES: LazyLock<Mutex<HashMap<i32, Vec<String>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Starts recording the files served to `browser_id`.
+pub(crate) fn watch_served_files(browser_id: i32) {
+    if let Ok(mut served) = SERVED_FILES.lock() {
+        served.entry(browser_id).or_default();
+    }
+}
+
+pub(crate) fn unwatch_served_files(browser_id: i32) {
+    if let Ok(mut served) = SERVED_FILES.lock() {
+        served.remove(&browser_id);
+    }
+}
+
+/// Records that `path` was served to `browser_id`, if that browser is watched.
+pub(crate) fn record_served_file(browser_id: i32, path: &str) {
+    let Ok(mut served) = SERVED_FILES.lock() else {
+        return;
+    };
+    if let Some(paths) = served.get_mut(&browser_id)
+        && !paths.iter().any(|existing| existing == path)
+    {
+        paths.push(path.to_string());
+    }
+}
+
+/// Takes the paths served to `browser_id` since the last call.
+pub(crate) fn take_served_files(browser_id: i32) -> Vec<String> {
+    SERVED_FILES
+        .lock()
+        .ok()
+        .and_then(|mut served| served.get_mut(&browser_id).map(std::mem::take))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_watched_browsers_are_recorded() {
+        record_served_file(-20, "res://ui/index.html");
+        assert!(take_served_files(-20).is_empty());
+
+        watch_served_files(-21);
+        record_served_file(-21, "res://ui/index.html");
+        record_served_file(-21, "res://ui/style.css");
+        record_served_file(-21, "res://ui/index.html");
+        assert_eq!(
+            take_served_files(-21),
+            vec![
+                "res://ui/index.html".to_string(),
+                "res://ui/style.css".to_string()
+            ]
+        );
+        assert!(take_served_files(-21).is_empty());
+
+        unwatch_served_files(-21);
+        record_served_file(-21, "res://ui/app.js");
+        assert!(take_served_files(-21).is_empty());
+    }
+}
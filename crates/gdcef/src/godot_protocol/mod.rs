@@ -8,15 +8,46 @@
 //!
 //! - `res://` - Access resources from Godot's packed resource system
 //! - `user://` - Access files from Godot's user data directory
+//!
+//! Projects can also declare their own schemes (e.g. `game://`) that are
+//! answered by GDScript callables; see [`custom_scheme`]. In-memory bundles,
+//! ZIP archives and extra directories can be mounted under a `res://` or
+//! `user://` prefix; see [`vfs`]. Assets that export replaced with their
+//! imported form are re-encoded on demand; see [`imported`]. During
+//! development a `res://` prefix can be proxied to a dev server; see
+//! [`dev_proxy`].
 
+mod access;
+mod cache;
+mod custom_scheme;
+mod dev_proxy;
+mod encoding;
 mod handler;
+mod headers;
+mod imported;
+mod live_reload;
 mod mime;
 mod multipart;
 mod range;
+mod stream;
+mod upstream;
+mod vfs;
+mod write;
 
+pub use custom_scheme::register_custom_scheme_handlers_on_context;
+pub(crate) use custom_scheme::{
+    CustomSchemeResourceHandler, CustomSchemeResourceHandlerImpl, CustomSchemeResponse,
+    DeferredResponse, FetchedResponseHook, normalize_scheme_name,
+    process_pending_custom_scheme_requests, read_request_body, read_request_headers,
+    register_custom_scheme_handler, response_from_dictionary, unregister_custom_scheme_handler,
+};
 pub use handler::{
     register_res_scheme_handler_on_context, register_user_scheme_handler_on_context,
 };
+pub(crate) use headers::glob_matches;
+pub(crate) use live_reload::{take_served_files, unwatch_served_files, watch_served_files};
+pub(crate) use vfs::{mount, mounted_prefixes, unmount};
+pub(crate) use write::take_user_files_written;
 
 /// Represents the Godot filesystem scheme type.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -7,6 +7,7 @@ use godot::classes::file_access::ModeFlags;
 use godot::prelude::*;
 
 use super::range::ByteRange;
+use super::stream::{BodySource, STREAM_CHUNK_SIZE};
 
 pub(crate) const MULTIPART_BOUNDARY: &str = "godot_cef_multipart_boundary";
 
@@ -85,7 +86,7 @@ pub(crate) fn read_multipart_streaming(
     file_path: &str,
     mime_type: &str,
     file_size: u64,
-    open_file: &mut Option<Gd<FileAccess>>,
+    open_file: &mut Option<BodySource>,
     data_out: *mut u8,
     bytes_to_read: usize,
 ) -> usize {
@@ -141,24 +142,19 @@ pub(crate) fn read_multipart_streaming(
 
                 if open_file.is_none() {
                     let gstring_path = GString::from(file_path);
-                    *open_file = FileAccess::open(&gstring_path, ModeFlags::READ);
+                    *open_file =
+                        FileAccess::open(&gstring_path, ModeFlags::READ).map(BodySource::File);
                 }
 
                 if let Some(file) = open_file.as_mut() {
-                    file.seek(range.start + stream.current_range_offset);
-                    let to_read = (bytes_to_read - written).min(remaining_in_range as usize);
-                    let buffer = file.get_buffer(to_read as i64);
-                    let actual_read = buffer.len();
+                    let to_read = (bytes_to_read - written)
+                        .min(usize::try_from(remaining_in_range).unwrap_or(usize::MAX))
+                        .min(STREAM_CHUNK_SIZE);
+                    let actual_read =
+                        file.read_at(range.start + stream.current_range_offset, to_read, out_ptr);
 
                     if actual_read > 0 {
-                        unsafe {
-                            std::ptr::copy_nonoverlapping(
-                                buffer.as_slice().as_ptr(),
-                                out_ptr,
-                                actual_read,
-                            );
-                            out_ptr = out_ptr.add(actual_read);
-                        }
+                        out_ptr = unsafe { out_ptr.add(actual_read) };
                         written += actual_read;
                         stream.current_range_offset += actual_read as u64;
                     } else {
@@ -0,0 +1,202 @@
+//! Bounded streaming of file-backed response bodies.
+//!
+//! Plain 200 responses and single-range 206 responses are read from the
+//! retained `FileAccess` (or mounted in-memory buffer) on demand instead of
+//! being copied into the response state.
+
+use godot::classes::FileAccess;
+use godot::prelude::*;
+use std::sync::Arc;
+
+/// Upper bound on the number of bytes fetched from `FileAccess` per call.
+pub(crate) const STREAM_CHUNK_SIZE: usize = 64 * 1024;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct FileStreamState {
+    /// Absolute file offset of the first byte in the body.
+    pub start: u64,
+    /// Total number of body bytes.
+    pub length: u64,
+    /// Number of body bytes already read or skipped.
+    pub offset: u64,
+}
+
+impl FileStreamState {
+    pub fn new(start: u64, length: u64) -> Self {
+        Self {
+            start,
+            length,
+            offset: 0,
+        }
+    }
+
+    pub fn remaining(&self) -> u64 {
+        self.length.saturating_sub(self.offset)
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.remaining() == 0
+    }
+
+    /// Size of the next chunk to fetch when `wanted` bytes are still requested.
+    pub fn next_chunk_len(&self, wanted: usize) -> usize {
+        let remaining = usize::try_from(self.remaining()).unwrap_or(usize::MAX);
+        wanted.min(remaining).min(STREAM_CHUNK_SIZE)
+    }
+
+    /// Advance past `bytes_to_skip` body bytes, returning the number skipped.
+    pub fn skip(&mut self, bytes_to_skip: usize) -> usize {
+        let remaining = usize::try_from(self.remaining()).unwrap_or(usize::MAX);
+        let skipped = bytes_to_skip.min(remaining);
+        self.offset += skipped as u64;
+        skipped
+    }
+
+    /// Stop the stream early, e.g. after a short read.
+    pub fn abort(&mut self) {
+        self.offset = self.length;
+    }
+}
+
+/// Backing storage of a response body.
+#[derive(Clone)]
+pub(crate) enum BodySource {
+    File(Gd<FileAccess>),
+    /// Bytes served from a `CefVirtualFs` mount.
+    Memory(Arc<[u8]>),
+}
+
+impl BodySource {
+    /// Copies up to `max_len` bytes starting at `position` into `out`,
+    /// returning the number of bytes written. `out` must have room for `max_len` bytes.
+    pub fn read_at(&mut self, position: u64, max_len: usize, out: *mut u8) -> usize {
+        match self {
+            BodySource::File(file) => {
+                if file.get_position() != position {
+                    file.seek(position);
+                }
+                let buffer = file.get_buffer(max_len as i64);
+                let actual_read = buffer.len().min(max_len);
+                unsafe {
+                    std::ptr::copy_nonoverlapping(buffer.as_slice().as_ptr(), out, actual_read);
+                }
+                actual_read
+            }
+            BodySource::Memory(bytes) => {
+                let start = usize::try_from(position)
+                    .unwrap_or(usize::MAX)
+                    .min(bytes.len());
+                let actual_read = max_len.min(bytes.len() - start);
+                unsafe {
+                    std::ptr::copy_nonoverlapping(bytes.as_ptr().add(start), out, actual_read);
+                }
+                actual_read
+            }
+        }
+    }
+}
+
+pub(crate) fn read_file_streaming(
+    stream: &mut FileStreamState,
+    open_file: &mut Option<BodySource>,
+    data_out: *mut u8,
+    bytes_to_read: usize,
+) -> usize {
+    let mut written = 0usize;
+    let mut out_ptr = data_out;
+
+    while written < bytes_to_read && !stream.is_complete() {
+        let Some(file) = open_file.as_mut() else {
+            stream.abort();
+            break;
+        };
+
+        let position = stream.start + stream.offset;
+        let to_read = stream.next_chunk_len(bytes_to_read - written);
+        let actual_read = file.read_at(position, to_read, out_ptr);
+
+        if actual_read == 0 {
+            // EOF or error - end the body rather than loop forever
+            stream.abort();
+            break;
+        }
+
+        out_ptr = unsafe { out_ptr.add(actual_read) };
+        written += actual_read;
+        stream.offset += actual_read as u64;
+    }
+
+    if stream.is_complete() {
+        *open_file = None; // Close file when stream completes
+    }
+
+    written
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_chunk_len_is_bounded() {
+        let stream = FileStreamState::new(0, 10 * STREAM_CHUNK_SIZE as u64);
+        assert_eq!(stream.next_chunk_len(usize::MAX), STREAM_CHUNK_SIZE);
+        assert_eq!(stream.next_chunk_len(100), 100);
+
+        let mut tail = FileStreamState::new(500, 300);
+        tail.offset = 250;
+        assert_eq!(tail.next_chunk_len(4096), 50);
+    }
+
+    #[test]
+    fn test_skip_clamps_to_remaining() {
+        let mut stream = FileStreamState::new(100, 1000);
+        assert_eq!(stream.skip(400), 400);
+        assert_eq!(stream.remaining(), 600);
+        assert_eq!(stream.skip(10_000), 600);
+        assert!(stream.is_complete());
+        assert_eq!(stream.skip(1), 0);
+    }
+
+    #[test]
+    fn test_empty_body_is_complete() {
+        let stream = FileStreamState::new(0, 0);
+        assert!(stream.is_complete());
+        assert_eq!(stream.next_chunk_len(1024), 0);
+    }
+
+    #[test]
+    fn test_read_memory_stream() {
+        let bytes: Arc<[u8]> = Arc::from(&b"0123456789"[..]);
+        let mut source = Some(BodySource::Memory(bytes));
+        let mut stream = FileStreamState::new(2, 6);
+        let mut out = [0u8; 4];
+
+        let written = read_file_streaming(&mut stream, &mut source, out.as_mut_ptr(), out.len());
+        assert_eq!(&out[..written], b"2345");
+        assert!(source.is_some());
+
+        let written = read_file_streaming(&mut stream, &mut source, out.as_mut_ptr(), out.len());
+        assert_eq!(&out[..written], b"67");
+        assert!(stream.is_complete());
+        assert!(source.is_none());
+    }
+
+    #[test]
+    fn test_read_memory_past_end() {
+        let mut source = BodySource::Memory(Arc::from(&b"abc"[..]));
+        let mut out = [0u8; 8];
+        assert_eq!(source.read_at(1, 8, out.as_mut_ptr()), 2);
+        assert_eq!(&out[..2], b"bc");
+        assert_eq!(source.read_at(10, 8, out.as_mut_ptr()), 0);
+    }
+
+    #[test]
+    fn test_abort_ends_stream() {
+        let mut stream = FileStreamState::new(0, 1000);
+        stream.skip(10);
+        stream.abort();
+        assert!(stream.is_complete());
+        assert_eq!(stream.remaining(), 0);
+    }
+}
@@ -0,0 +1,155 @@
+//! Fetching a response with `cef::Urlrequest` for a deferred resource handler.
+//!
+//! The dev server proxy fetches `res://` requests from its upstream, and
+//! `CefTexture` response filters fetch the original request so the whole body
+//! can be filtered on the main thread before the page receives it.
+
+use cef::{
+    CefStringUtf16, Errorcode, ImplRequest, ImplResponse, ImplUrlrequest, ImplUrlrequestClient,
+    Urlrequest, UrlrequestClient, UrlrequestStatus, WrapUrlrequestClient, rc::Rc,
+    wrap_urlrequest_client,
+};
+use std::sync::{Arc, Mutex};
+
+use super::custom_scheme::{
+    CustomRequestStateHandle, CustomSchemeResponse, DeferredResponse, FetchedResponseHook,
+    complete_request, read_request_headers,
+};
+
+/// Response headers that describe the upstream transfer rather than the body
+/// `Urlrequest` hands us, which is already decoded.
+const DROPPED_RESPONSE_HEADERS: &[&str] =
+    &["Content-Length", "Content-Encoding", "Transfer-Encoding"];
+
+/// Receives the upstream response, or the network error the request failed with.
+pub(crate) type UpstreamResponseHandler =
+    Arc<dyn Fn(Result<CustomSchemeResponse, Errorcode>) + Send + Sync>;
+
+fn response_from_upstream(response: &cef::Response, body: Vec<u8>) -> CustomSchemeResponse {
+    let mut header_map = cef::CefStringMultimap::new();
+    response.header_map(Some(&mut header_map));
+    let headers = header_map
+        .into_iter()
+        .filter(|(name, _)| {
+            !DROPPED_RESPONSE_HEADERS
+                .iter()
+                .any(|dropped| name.eq_ignore_ascii_case(dropped))
+        })
+        .flat_map(|(name, values)| values.into_iter().map(move |value| (name.clone(), value)))
+        .collect();
+
+    CustomSchemeResponse {
+        status: response.status(),
+        headers,
+        body,
+    }
+}
+
+wrap_urlrequest_client! {
+    struct UpstreamClient {
+        body: Arc<Mutex<Vec<u8>>>,
+        on_response: UpstreamResponseHandler,
+    }
+
+    impl UrlrequestClient {
+        fn on_download_data(
+            &self,
+            _request: Option<&mut Urlrequest>,
+            data: *const u8,
+            data_length: usize,
+        ) {
+            if data.is_null() || data_length == 0 {
+                return;
+            }
+            let chunk = unsafe { std::slice::from_raw_parts(data, data_length) };
+            if let Ok(mut body) = self.body.lock() {
+                body.extend_from_slice(chunk);
+            }
+        }
+
+        fn on_request_complete(&self, request: Option<&mut Urlrequest>) {
+            let body = self.body.lock().map(|mut body| std::mem::take(&mut *body)).unwrap_or_default();
+            let Some(request) = request else {
+                (self.on_response)(Err(Errorcode::FAILED));
+                return;
+            };
+            // A redirect stopped by `UR_FLAG_STOP_ON_REDIRECT` is handed on as it is
+            let response = request.response().filter(|response| {
+                request.request_status() == UrlrequestStatus::SUCCESS
+                    || (300..400).contains(&response.status())
+            });
+            (self.on_response)(match response {
+                Some(response) => Ok(response_from_upstream(&response, body)),
+                None => Err(request.request_error()),
+            });
+        }
+    }
+}
+
+/// Copies the URL, method, headers and body of `request` so it can be sent
+/// again with `Urlrequest`. Cookies of the request context are sent and stored.
+/// Redirects are not followed, so the page follows them under the right URL.
+fn copy_request(request: &cef::Request) -> Option<cef::Request> {
+    let copy = cef::request_create()?;
+    let url = CefStringUtf16::from(&request.url()).to_string();
+    let method = CefStringUtf16::from(&request.method()).to_string();
+    copy.set_url(Some(&url.as_str().into()));
+    copy.set_method(Some(&method.as_str().into()));
+    for (name, value) in read_request_headers(request) {
+        copy.set_header_by_name(
+            Some(&name.as_str().into()),
+            Some(&value.as_str().into()),
+            true as _,
+        );
+    }
+    if let Some(mut post_data) = request.post_data() {
+        copy.set_post_data(Some(&mut post_data));
+    }
+    copy.set_flags(crate::cef_raw_to_i32!(
+        cef::sys::cef_urlrequest_flags_t::UR_FLAG_ALLOW_STORED_CREDENTIALS.0
+            | cef::sys::cef_urlrequest_flags_t::UR_FLAG_STOP_ON_REDIRECT.0
+    ));
+    Some(copy)
+}
+
+/// Sends `request` through `context`, or the global request context when
+/// `None`, and passes the response to `on_response`.
+pub(super) fn start_upstream_request(
+    request: &mut cef::Request,
+    context: Option<&mut cef::RequestContext>,
+    on_response: UpstreamResponseHandler,
+) -> Option<Urlrequest> {
+    let mut client = UpstreamClient::new(Arc::new(Mutex::new(Vec::new())), on_response.clone());
+    let url_request = cef::urlrequest_create(Some(request), Some(&mut client), context);
+    if url_request.is_none() {
+        on_response(Err(Errorcode::FAILED));
+    }
+    url_request
+}
+
+/// Fetches `request` again through `context` and hands the response to
+/// `on_fetched`, which completes `state` now or later.
+pub(super) fn start_fetch(
+    request: &cef::Request,
+    mut context: Option<cef::RequestContext>,
+    on_fetched: FetchedResponseHook,
+    state: &CustomRequestStateHandle,
+) -> Option<Urlrequest> {
+    let Some(mut copy) = copy_request(request) else {
+        complete_request(
+            state,
+            CustomSchemeResponse::error(500, "Failed to copy request"),
+        );
+        return None;
+    };
+    let deferred = DeferredResponse::new(state);
+    start_upstream_request(
+        &mut copy,
+        context.as_mut(),
+        Arc::new(move |response| match response {
+            Ok(response) => on_fetched(response, deferred.clone()),
+            // The page sees the browser's own network error
+            Err(error) => deferred.fail(error),
+        }),
+    )
+}
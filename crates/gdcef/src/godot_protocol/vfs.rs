@@ -0,0 +1,362 @@
+//! Virtual filesystem mounts for the `res://` and `user://` handlers.
+//!
+//! A mount overlays a URL prefix with files that are not on Godot's
+//! filesystem: an in-memory `Dictionary`, an opened `ZIPReader`, or another
+//! directory. The scheme handler resolves paths through [`Filesystem`], which
+//! consults the mounts before falling back to `FileAccess`, and finally to
+//! re-encoded imported resources.
+
+use godot::classes::file_access::ModeFlags;
+use godot::classes::{DirAccess, FileAccess, ZipReader};
+use godot::global::godot_warn;
+use godot::prelude::*;
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, RwLock};
+
+use super::GodotScheme;
+use super::cache::{Validators, file_validators};
+use super::imported::cached_resource;
+use super::stream::BodySource;
+
+/// An in-memory file with its precomputed entity tag.
+struct MemoryFile {
+    data: Arc<[u8]>,
+    etag: String,
+}
+
+enum MountSource {
+    /// Files from a `Dictionary`, or the entries of a `ZIPReader` read at mount time.
+    Memory(HashMap<String, MemoryFile>),
+    /// Base path without a trailing slash, e.g. `user://dlc/ui` or an OS path.
+    Directory(String),
+}
+
+pub(crate) struct Mount {
+    /// Normalized prefix ending with `/`, e.g. `res://dlc/`.
+    prefix: String,
+    source: MountSource,
+}
+
+static MOUNTS: RwLock<Vec<Arc<Mount>>> = RwLock::new(Vec::new());
+
+/// A file opened for serving.
+pub(crate) struct OpenedFile {
+    pub body: BodySource,
+    pub size: u64,
+    pub validators: Validators,
+    /// Overrides the extension-based MIME type, e.g. an SVG re-encoded as PNG.
+    pub mime_type: Option<&'static str>,
+}
+
+pub(super) fn memory_etag(data: &[u8]) -> String {
+    let mut hasher = DefaultHasher::new();
+    data.hash(&mut hasher);
+    format!("\"vfs-{:x}-{:016x}\"", data.len(), hasher.finish())
+}
+
+fn memory_validators(etag: String) -> Validators {
+    Validators {
+        etag: Some(etag),
+        modified_time: None,
+    }
+}
+
+/// Normalizes a mount prefix to `res://some/dir/` form.
+///
+/// Returns `None` unless the prefix belongs to `res://` or `user://` and is
+/// free of traversal segments.
+pub(crate) fn normalize_mount_prefix(prefix: &str) -> Option<String> {
+    let prefix = prefix.trim();
+    let (scheme, rest) = [GodotScheme::Res, GodotScheme::User]
+        .into_iter()
+        .find_map(|scheme| {
+            prefix
+                .strip_prefix(scheme.prefix())
+                .map(|rest| (scheme, rest))
+        })?;
+
+    let segments: Vec<&str> = rest.split('/').filter(|s| !s.is_empty()).collect();
+    if segments.iter().any(|s| *s == "." || *s == "..") {
+        return None;
+    }
+
+    let mut normalized = String::from(scheme.prefix());
+    for segment in segments {
+        normalized.push_str(segment);
+        normalized.push('/');
+    }
+    Some(normalized)
+}
+
+/// Normalizes a path inside a mount source, e.g. `/ui//index.html` -> `ui/index.html`.
+fn normalize_entry_path(path: &str) -> String {
+    path.split('/')
+        .filter(|s| !s.is_empty() && *s != ".")
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn memory_file(data: &[u8]) -> MemoryFile {
+    let data: Arc<[u8]> = Arc::from(data);
+    let etag = memory_etag(&data);
+    MemoryFile { data, etag }
+}
+
+fn memory_source(files: &VarDictionary) -> Option<MountSource> {
+    let mut entries = HashMap::new();
+    for (key, value) in files.iter_shared() {
+        let Ok(bytes) = value.try_to::<PackedByteArray>() else {
+            godot_warn!(
+                "[CefVirtualFs] Ignoring '{}': values must be PackedByteArray",
+                key
+            );
+            continue;
+        };
+        entries.insert(
+            normalize_entry_path(&key.to_string()),
+            memory_file(bytes.as_slice()),
+        );
+    }
+    Some(MountSource::Memory(entries))
+}
+
+/// Decompresses every entry on the calling (main) thread, so the IO thread
+/// never touches the `ZIPReader`.
+fn zip_source(mut reader: Gd<ZipReader>) -> Option<MountSource> {
+    let names = reader.get_files();
+    if names.is_empty() {
+        godot_warn!("[CefVirtualFs] ZIPReader is not open or the archive is empty");
+        return None;
+    }
+    let mut entries = HashMap::new();
+    for name in names.as_slice() {
+        let path = name.to_string();
+        // Directories end with `/`; they exist implicitly through their files
+        if path.ends_with('/') {
+            continue;
+        }
+        let bytes = reader.read_file(name);
+        entries.insert(normalize_entry_path(&path), memory_file(bytes.as_slice()));
+    }
+    Some(MountSource::Memory(entries))
+}
+
+fn directory_source(path: &str) -> Option<MountSource> {
+    let path = path.trim().trim_end_matches(['/', '\\']);
+    if path.is_empty() || !DirAccess::dir_exists_absolute(&GString::from(path)) {
+        godot_warn!("[CefVirtualFs] Directory '{}' does not exist", path);
+        return None;
+    }
+    Some(MountSource::Directory(path.to_string()))
+}
+
+/// Mounts `source` at `prefix`, replacing an existing mount at the same prefix.
+pub(crate) fn mount(prefix: &str, source: &Variant) -> bool {
+    let Some(prefix) = normalize_mount_prefix(prefix) else {
+        godot_warn!(
+            "[CefVirtualFs] Invalid mount prefix '{}': expected res://... or user://...",
+            prefix
+        );
+        return false;
+    };
+
+    let source = if let Ok(files) = source.try_to::<VarDictionary>() {
+        memory_source(&files)
+    } else if let Ok(reader) = source.try_to::<Gd<ZipReader>>() {
+        zip_source(reader)
+    } else if let Ok(path) = source.try_to::<GString>() {
+        directory_source(&path.to_string())
+    } else {
+        godot_warn!(
+            "[CefVirtualFs] Unsupported mount source for '{}': expected Dictionary, ZIPReader or directory path",
+            prefix
+        );
+        None
+    };
+    let Some(source) = source else {
+        return false;
+    };
+
+    let Ok(mut mounts) = MOUNTS.write() else {
+        return false;
+    };
+    mounts.retain(|mount| mount.prefix != prefix);
+    mounts.push(Arc::new(Mount { prefix, source }));
+    true
+}
+
+pub(crate) fn unmount(prefix: &str) -> bool {
+    let Some(prefix) = normalize_mount_prefix(prefix) else {
+        return false;
+    };
+    let Ok(mut mounts) = MOUNTS.write() else {
+        return false;
+    };
+    let before = mounts.len();
+    mounts.retain(|mount| mount.prefix != prefix);
+    mounts.len() != before
+}
+
+pub(crate) fn mounted_prefixes() -> Vec<String> {
+    MOUNTS
+        .read()
+        .map(|mounts| mounts.iter().map(|mount| mount.prefix.clone()).collect())
+        .unwrap_or_default()
+}
+
+/// Where request paths are looked up.
+pub(crate) enum Filesystem {
+    Godot,
+    Mounted(Arc<Mount>),
+    /// Already converted imported resources whose source file was not exported.
+    Imported,
+}
+
+impl Filesystem {
+    /// Returns the mount with the longest prefix containing `godot_path`, if any.
+    pub fn mount_for(godot_path: &str) -> Option<Filesystem> {
+        let mounts = MOUNTS.read().ok()?;
+        mounts
+            .iter()
+            .filter(|mount| godot_path.starts_with(mount.prefix.as_str()))
+            .max_by_key(|mount| mount.prefix.len())
+            .map(|mount| Filesystem::Mounted(Arc::clone(mount)))
+    }
+
+    pub fn file_exists(&self, path: &str) -> bool {
+        match self {
+            Filesystem::Godot => FileAccess::file_exists(&GString::from(path)),
+            Filesystem::Imported => cached_resource(path).is_some(),
+            Filesystem::Mounted(mount) => {
+                let Some(relative) = mount.relative_path(path) else {
+                    return false;
+                };
+                match &mount.source {
+                    MountSource::Memory(files) => files.contains_key(&relative),
+                    MountSource::Directory(base) => {
+                        FileAccess::file_exists(&GString::from(join_path(base, &relative).as_str()))
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn dir_exists(&self, path: &str) -> bool {
+        match self {
+            Filesystem::Godot => DirAccess::dir_exists_absolute(&GString::from(path)),
+            Filesystem::Imported => false,
+            Filesystem::Mounted(mount) => {
+                let Some(relative) = mount.relative_path(path) else {
+                    return false;
+                };
+                let dir_prefix = if relative.is_empty() {
+                    String::new()
+                } else {
+                    format!("{}/", relative)
+                };
+                match &mount.source {
+                    MountSource::Memory(files) => {
+                        files.keys().any(|key| key.starts_with(&dir_prefix))
+                    }
+                    MountSource::Directory(base) => DirAccess::dir_exists_absolute(&GString::from(
+                        join_path(base, &relative).as_str(),
+                    )),
+                }
+            }
+        }
+    }
+
+    pub fn open(&self, path: &str) -> Option<OpenedFile> {
+        match self {
+            Filesystem::Godot => open_godot_file(path),
+            Filesystem::Imported => {
+                let resource = cached_resource(path)?;
+                Some(OpenedFile {
+                    body: BodySource::Memory(Arc::clone(&resource.data)),
+                    size: resource.data.len() as u64,
+                    validators: memory_validators(resource.etag.clone()),
+                    mime_type: Some(resource.mime_type),
+                })
+            }
+            Filesystem::Mounted(mount) => {
+                let relative = mount.relative_path(path)?;
+                match &mount.source {
+                    MountSource::Memory(files) => {
+                        let file = files.get(&relative)?;
+                        Some(OpenedFile {
+                            body: BodySource::Memory(Arc::clone(&file.data)),
+                            size: file.data.len() as u64,
+                            validators: memory_validators(file.etag.clone()),
+                            mime_type: None,
+                        })
+                    }
+                    MountSource::Directory(base) => open_godot_file(&join_path(base, &relative)),
+                }
+            }
+        }
+    }
+}
+
+impl Mount {
+    fn relative_path(&self, godot_path: &str) -> Option<String> {
+        godot_path
+            .strip_prefix(self.prefix.as_str())
+            .map(normalize_entry_path)
+    }
+}
+
+fn join_path(base: &str, relative: &str) -> String {
+    if relative.is_empty() {
+        base.to_string()
+    } else {
+        format!("{}/{}", base, relative)
+    }
+}
+
+fn open_godot_file(path: &str) -> Option<OpenedFile> {
+    let file = FileAccess::open(&GString::from(path), ModeFlags::READ)?;
+    let size = file.get_length();
+    Some(OpenedFile {
+        body: BodySource::File(file),
+        size,
+        validators: file_validators(path, size),
+        mime_type: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_mount_prefix() {
+        assert_eq!(
+            normalize_mount_prefix("res://dlc"),
+            Some("res://dlc/".to_string())
+        );
+        assert_eq!(
+            normalize_mount_prefix("user://bundles//ui/"),
+            Some("user://bundles/ui/".to_string())
+        );
+        assert_eq!(normalize_mount_prefix("res://"), Some("res://".to_string()));
+        assert_eq!(normalize_mount_prefix("res://a/../b"), None);
+        assert_eq!(normalize_mount_prefix("https://example.com"), None);
+        assert_eq!(normalize_mount_prefix("dlc/"), None);
+    }
+
+    #[test]
+    fn test_normalize_entry_path() {
+        assert_eq!(normalize_entry_path("/ui//index.html"), "ui/index.html");
+        assert_eq!(normalize_entry_path("./app.js"), "app.js");
+        assert_eq!(normalize_entry_path(""), "");
+    }
+
+    #[test]
+    fn test_memory_etag_depends_on_content() {
+        assert_eq!(memory_etag(b"abc"), memory_etag(b"abc"));
+        assert_ne!(memory_etag(b"abc"), memory_etag(b"abd"));
+        assert!(memory_etag(b"abc").starts_with("\"vfs-3-"));
+    }
+}
@@ -0,0 +1,361 @@
+//! Opt-in write support for `user://`.
+//!
+//! `PUT`/`POST` store the request body through `FileAccess` and `DELETE`
+//! removes the file, but only below the prefixes listed in
+//! `godot_cef/schemes/user_writable_paths`. Every write is checked against a
+//! per-file size limit and a quota for the writable directory it lands in.
+
+use godot::classes::file_access::ModeFlags;
+use godot::classes::{DirAccess, FileAccess};
+use godot::global::Error;
+use godot::prelude::*;
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::Mutex;
+
+const BYTES_PER_MB: u64 = 1024 * 1024;
+
+/// Upper bound on undelivered `user_file_written` notifications, so entries
+/// for browsers that closed before draining them cannot grow without limit.
+const MAX_PENDING_WRITE_EVENTS: usize = 1024;
+
+/// Written paths by browser identifier, drained by `CefTexture` each frame.
+static WRITTEN_FILES: Mutex<VecDeque<(i32, String)>> = Mutex::new(VecDeque::new());
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum WriteMethod {
+    Put,
+    Post,
+    Delete,
+}
+
+impl WriteMethod {
+    pub fn parse(method: &str) -> Option<Self> {
+        if method.eq_ignore_ascii_case("PUT") {
+            Some(WriteMethod::Put)
+        } else if method.eq_ignore_ascii_case("POST") {
+            Some(WriteMethod::Post)
+        } else if method.eq_ignore_ascii_case("DELETE") {
+            Some(WriteMethod::Delete)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum WriteOutcome {
+    Created,
+    Replaced,
+    Deleted,
+}
+
+impl WriteOutcome {
+    pub fn status_code(&self) -> i32 {
+        match self {
+            WriteOutcome::Created => 201,
+            WriteOutcome::Replaced | WriteOutcome::Deleted => 204,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum WriteError {
+    NotWritable,
+    InvalidPath,
+    TooLarge { limit: u64 },
+    QuotaExceeded { quota: u64 },
+    NotFound,
+    Failed(String),
+}
+
+impl WriteError {
+    pub fn status_code(&self) -> i32 {
+        match self {
+            WriteError::NotWritable => 403,
+            WriteError::InvalidPath => 400,
+            WriteError::TooLarge { .. } => 413,
+            WriteError::QuotaExceeded { .. } => 507,
+            WriteError::NotFound => 404,
+            WriteError::Failed(_) => 500,
+        }
+    }
+}
+
+impl fmt::Display for WriteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WriteError::NotWritable => write!(
+                f,
+                "path is not listed in godot_cef/schemes/user_writable_paths"
+            ),
+            WriteError::InvalidPath => write!(f, "writes must target a file"),
+            WriteError::TooLarge { limit } => {
+                write!(f, "request body exceeds the {} byte file size limit", limit)
+            }
+            WriteError::QuotaExceeded { quota } => {
+                write!(f, "write would exceed the {} byte quota", quota)
+            }
+            WriteError::NotFound => write!(f, "file does not exist"),
+            WriteError::Failed(reason) => write!(f, "{}", reason),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct UserWritePolicy {
+    /// Normalized prefixes ending with `/`. Empty means `user://` is read-only.
+    pub writable_prefixes: Vec<String>,
+    /// Maximum size of a single file in bytes, `0` for no limit.
+    pub max_file_size: u64,
+    /// Maximum total size below each writable prefix in bytes, `0` for no limit.
+    pub quota: u64,
+}
+
+impl UserWritePolicy {
+    pub fn from_settings() -> Self {
+        Self {
+            writable_prefixes: crate::settings::get_user_writable_paths(),
+            max_file_size: crate::settings::get_user_write_max_file_size_mb()
+                .saturating_mul(BYTES_PER_MB),
+            quota: crate::settings::get_user_write_quota_mb().saturating_mul(BYTES_PER_MB),
+        }
+    }
+
+    /// Returns the writable prefix containing `path`, preferring the longest.
+    pub fn root_for(&self, path: &str) -> Option<&str> {
+        self.writable_prefixes
+            .iter()
+            .filter(|prefix| path.len() > prefix.len() && path.starts_with(prefix.as_str()))
+            .max_by_key(|prefix| prefix.len())
+            .map(String::as_str)
+    }
+
+    /// Validates a write of `body_len` bytes replacing `existing_size` bytes
+    /// in a directory that currently holds `used` bytes.
+    pub fn check_write(
+        &self,
+        body_len: u64,
+        existing_size: u64,
+        used: u64,
+    ) -> Result<(), WriteError> {
+        if self.max_file_size > 0 && body_len > self.max_file_size {
+            return Err(WriteError::TooLarge {
+                limit: self.max_file_size,
+            });
+        }
+        if self.quota > 0
+            && used.saturating_sub(existing_size).saturating_add(body_len) > self.quota
+        {
+            return Err(WriteError::QuotaExceeded { quota: self.quota });
+        }
+        Ok(())
+    }
+}
+
+fn file_size(path: &str) -> Option<u64> {
+    FileAccess::open(&GString::from(path), ModeFlags::READ).map(|file| file.get_length())
+}
+
+/// Total size of all files below `dir`, recursively.
+fn directory_usage(dir: &str) -> u64 {
+    let dir = dir.trim_end_matches('/');
+    let gstring_dir = GString::from(dir);
+
+    let files: u64 = DirAccess::get_files_at(&gstring_dir)
+        .as_slice()
+        .iter()
+        .filter_map(|name| file_size(&format!("{}/{}", dir, name)))
+        .sum();
+    let subdirectories: u64 = DirAccess::get_directories_at(&gstring_dir)
+        .as_slice()
+        .iter()
+        .map(|name| directory_usage(&format!("{}/{}", dir, name)))
+        .sum();
+
+    files + subdirectories
+}
+
+/// Stores `body` at `path` (a decoded `user://` path) for `PUT`/`POST`.
+pub(crate) fn write_user_file(
+    policy: &UserWritePolicy,
+    path: &str,
+    body: &[u8],
+) -> Result<WriteOutcome, WriteError> {
+    let root = policy.root_for(path).ok_or(WriteError::NotWritable)?;
+    if path.ends_with('/') {
+        return Err(WriteError::InvalidPath);
+    }
+
+    let existing_size = file_size(path);
+    policy.check_write(
+        body.len() as u64,
+        existing_size.unwrap_or(0),
+        directory_usage(root),
+    )?;
+
+    if let Some((parent, _)) = path.rsplit_once('/') {
+        let result = DirAccess::make_dir_recursive_absolute(&GString::from(parent));
+        if result != Error::OK {
+            return Err(WriteError::Failed(format!(
+                "could not create directory {}: {:?}",
+                parent, result
+            )));
+        }
+    }
+
+    let Some(mut file) = FileAccess::open(&GString::from(path), ModeFlags::WRITE) else {
+        return Err(WriteError::Failed(format!(
+            "could not open {} for writing: {:?}",
+            path,
+            FileAccess::get_open_error()
+        )));
+    };
+    file.store_buffer(&PackedByteArray::from(body));
+    let result = file.get_error();
+    file.close();
+    if result != Error::OK {
+        return Err(WriteError::Failed(format!(
+            "could not write {}: {:?}",
+            path, result
+        )));
+    }
+
+    Ok(if existing_size.is_some() {
+        WriteOutcome::Replaced
+    } else {
+        WriteOutcome::Created
+    })
+}
+
+/// Removes the file at `path` (a decoded `user://` path) for `DELETE`.
+pub(crate) fn delete_user_file(
+    policy: &UserWritePolicy,
+    path: &str,
+) -> Result<WriteOutcome, WriteError> {
+    policy.root_for(path).ok_or(WriteError::NotWritable)?;
+    if !FileAccess::file_exists(&GString::from(path)) {
+        return Err(WriteError::NotFound);
+    }
+
+    let result = DirAccess::remove_absolute(&GString::from(path));
+    if result != Error::OK {
+        return Err(WriteError::Failed(format!(
+            "could not delete {}: {:?}",
+            path, result
+        )));
+    }
+    Ok(WriteOutcome::Deleted)
+}
+
+/// Queues a `user_file_written` notification for the browser that made the request.
+pub(crate) fn record_user_file_written(browser_id: i32, path: &str) {
+    let Ok(mut written) = WRITTEN_FILES.lock() else {
+        return;
+    };
+    if written.len() >= MAX_PENDING_WRITE_EVENTS {
+        written.pop_front();
+    }
+    written.push_back((browser_id, path.to_string()));
+}
+
+/// Takes the paths written by `browser_id` since the last call.
+pub(crate) fn take_user_files_written(browser_id: i32) -> Vec<String> {
+    let Ok(mut written) = WRITTEN_FILES.lock() else {
+        return Vec::new();
+    };
+    if written.is_empty() {
+        return Vec::new();
+    }
+
+    let mut taken = Vec::new();
+    written.retain(|(id, path)| {
+        if *id == browser_id {
+            taken.push(path.clone());
+            false
+        } else {
+            true
+        }
+    });
+    taken
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(prefixes: &[&str], max_file_size: u64, quota: u64) -> UserWritePolicy {
+        UserWritePolicy {
+            writable_prefixes: prefixes.iter().map(|s| s.to_string()).collect(),
+            max_file_size,
+            quota,
+        }
+    }
+
+    #[test]
+    fn test_write_method_parse() {
+        assert_eq!(WriteMethod::parse("put"), Some(WriteMethod::Put));
+        assert_eq!(WriteMethod::parse("POST"), Some(WriteMethod::Post));
+        assert_eq!(WriteMethod::parse("Delete"), Some(WriteMethod::Delete));
+        assert_eq!(WriteMethod::parse("GET"), None);
+        assert_eq!(WriteMethod::parse("PATCH"), None);
+    }
+
+    #[test]
+    fn test_root_for_prefers_longest_prefix() {
+        let policy = policy(&["user://levels/", "user://levels/shared/"], 0, 0);
+        assert_eq!(
+            policy.root_for("user://levels/a.json"),
+            Some("user://levels/")
+        );
+        assert_eq!(
+            policy.root_for("user://levels/shared/b.json"),
+            Some("user://levels/shared/")
+        );
+        assert_eq!(policy.root_for("user://levels/"), None);
+        assert_eq!(policy.root_for("user://saves/slot1.json"), None);
+        assert_eq!(policy.root_for("user://levelsX/a.json"), None);
+    }
+
+    #[test]
+    fn test_check_write_limits() {
+        let policy = policy(&["user://levels/"], 100, 1000);
+        assert!(policy.check_write(100, 0, 900).is_ok());
+        assert_eq!(
+            policy.check_write(101, 0, 0),
+            Err(WriteError::TooLarge { limit: 100 })
+        );
+        assert_eq!(
+            policy.check_write(50, 0, 960),
+            Err(WriteError::QuotaExceeded { quota: 1000 })
+        );
+        // Replacing a file only counts the size difference
+        assert!(policy.check_write(90, 60, 960).is_ok());
+    }
+
+    #[test]
+    fn test_check_write_unlimited() {
+        let policy = policy(&["user://levels/"], 0, 0);
+        assert!(policy.check_write(u64::MAX, 0, u64::MAX).is_ok());
+    }
+
+    #[test]
+    fn test_written_files_are_taken_per_browser() {
+        record_user_file_written(-7, "user://levels/a.json");
+        record_user_file_written(-8, "user://levels/b.json");
+        record_user_file_written(-7, "user://levels/c.json");
+
+        assert_eq!(
+            take_user_files_written(-7),
+            vec![
+                "user://levels/a.json".to_string(),
+                "user://levels/c.json".to_string()
+            ]
+        );
+        assert!(take_user_files_written(-7).is_empty());
+        assert_eq!(
+            take_user_files_written(-8),
+            vec!["user://levels/b.json".to_string()]
+        );
+    }
+}
@@ -1,17 +1,24 @@
 mod accelerated_osr;
+mod adblock_engine;
+mod auth;
 mod browser;
 mod cef_init;
 mod cef_ipc_inspector;
+mod cef_scheme_registry;
 mod cef_texture;
 mod cef_texture2d;
+mod cef_virtual_fs;
+mod certificate;
 mod compat;
 mod cookie;
 mod cursor;
+mod devtools;
 mod drag;
 mod error;
 mod godot_protocol;
 mod input;
 mod ipc_data;
+mod profiles;
 mod render;
 mod settings;
 mod utils;
@@ -50,5 +57,7 @@ unsafe impl ExtensionLibrary for GodotCef {
 
 // Re-export CefTexture for convenience
 pub use cef_ipc_inspector::CefIpcInspector;
+pub use cef_scheme_registry::CefSchemeRegistry;
 pub use cef_texture::CefTexture;
 pub use cef_texture2d::CefTexture2D;
+pub use cef_virtual_fs::CefVirtualFs;
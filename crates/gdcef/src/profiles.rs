@@ -0,0 +1,111 @@
+//! Request contexts for the `profile` property of `CefTexture`.
+//!
+//! An empty profile gets a fresh in-memory context, so nothing the page stores
+//! outlives the browser. A named profile keeps its cookies, localStorage and
+//! IndexedDB in `<data_path>/profiles/<name>`, and every browser using that
+//! name shares one context. Shared contexts are released before CEF shuts down.
+
+use cef::RequestContextSettings;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::adblock_engine::AdblockEngineHandle;
+use crate::{godot_protocol, webrender};
+
+const PROFILES_DIR_NAME: &str = "profiles";
+
+thread_local! {
+    // Contexts are only created and released on Godot's main thread
+    static PROFILE_CONTEXTS: RefCell<HashMap<String, cef::RequestContext>> =
+        RefCell::new(HashMap::new());
+}
+
+/// Returns the cache directory of a named profile, or `None` if the name could escape `root`.
+fn profile_cache_path(root: &Path, profile: &str) -> Option<PathBuf> {
+    let valid = !profile.is_empty()
+        && !profile.starts_with('.')
+        && profile
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    valid.then(|| root.join(PROFILES_DIR_NAME).join(profile))
+}
+
+/// Creates a request context with the `res://`, `user://` and custom scheme handlers.
+fn create_context(
+    settings: &RequestContextSettings,
+    adblock_engine: Option<AdblockEngineHandle>,
+) -> Option<cef::RequestContext> {
+    let mut context = cef::request_context_create_context(
+        Some(settings),
+        Some(&mut webrender::RequestContextHandlerImpl::build(
+            webrender::OsrRequestContextHandler::new(adblock_engine),
+        )),
+    )?;
+    godot_protocol::register_res_scheme_handler_on_context(&mut context);
+    godot_protocol::register_user_scheme_handler_on_context(&mut context);
+    godot_protocol::register_custom_scheme_handlers_on_context(&mut context);
+    Some(context)
+}
+
+/// Returns the request context for `profile`, creating it on first use.
+/// Invalid profile names fall back to an ephemeral context.
+pub(crate) fn request_context(
+    profile: &str,
+    adblock_engine: Option<AdblockEngineHandle>,
+    log_prefix: &str,
+) -> Option<cef::RequestContext> {
+    if profile.is_empty() {
+        return create_context(&RequestContextSettings::default(), adblock_engine);
+    }
+    if let Some(context) = PROFILE_CONTEXTS.with_borrow(|contexts| contexts.get(profile).cloned()) {
+        return Some(context);
+    }
+
+    let cache_path = profile_cache_path(&crate::settings::get_data_path(), profile);
+    let Some(cache_path) = cache_path.as_deref().and_then(Path::to_str) else {
+        godot::global::godot_warn!(
+            "[{}] Invalid profile name '{}'; use letters, digits, '-', '_' and '.'. Using an ephemeral profile.",
+            log_prefix,
+            profile
+        );
+        return create_context(&RequestContextSettings::default(), adblock_engine);
+    };
+    let settings = RequestContextSettings {
+        cache_path: cache_path.into(),
+        ..Default::default()
+    };
+    let context = create_context(&settings, adblock_engine)?;
+    PROFILE_CONTEXTS.with_borrow_mut(|contexts| {
+        contexts.insert(profile.to_string(), context.clone());
+    });
+    Some(context)
+}
+
+/// Drops the shared profile contexts. Must run before `cef::shutdown()`.
+pub(crate) fn release_profile_contexts() {
+    PROFILE_CONTEXTS.with_borrow_mut(HashMap::clear);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_cache_path() {
+        let root = Path::new("/data");
+        assert_eq!(
+            profile_cache_path(root, "player-1"),
+            Some(PathBuf::from("/data/profiles/player-1"))
+        );
+        assert_eq!(
+            profile_cache_path(root, "qa_eu.v2"),
+            Some(PathBuf::from("/data/profiles/qa_eu.v2"))
+        );
+        assert_eq!(profile_cache_path(root, ""), None);
+        assert_eq!(profile_cache_path(root, ".."), None);
+        assert_eq!(profile_cache_path(root, "a/b"), None);
+        assert_eq!(profile_cache_path(root, "a\\b"), None);
+        assert_eq!(profile_cache_path(root, "spaced name"), None);
+    }
+}
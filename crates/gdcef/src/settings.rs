@@ -7,6 +7,7 @@ use std::path::PathBuf;
 const SETTING_DATA_PATH: &str = "godot_cef/storage/data_path";
 const SETTING_ALLOW_INSECURE_CONTENT: &str = "godot_cef/security/allow_insecure_content";
 const SETTING_IGNORE_CERTIFICATE_ERRORS: &str = "godot_cef/security/ignore_certificate_errors";
+const SETTING_CERTIFICATE_EXCEPTION_HOSTS: &str = "godot_cef/security/certificate_exception_hosts";
 const SETTING_DISABLE_WEB_SECURITY: &str = "godot_cef/security/disable_web_security";
 const SETTING_DEFAULT_PERMISSION_POLICY: &str = "godot_cef/security/default_permission_policy";
 const SETTING_ENABLE_AUDIO_CAPTURE: &str = "godot_cef/audio/enable_audio_capture";
@@ -18,11 +19,31 @@ const SETTING_PROXY_SERVER: &str = "godot_cef/network/proxy_server";
 const SETTING_PROXY_BYPASS_LIST: &str = "godot_cef/network/proxy_bypass_list";
 const SETTING_ENABLE_ADBLOCK: &str = "godot_cef/network/enable_adblock";
 const SETTING_ADBLOCK_RULES_PATH: &str = "godot_cef/network/adblock_rules_path";
+const SETTING_ADBLOCK_COSMETIC_FILTERING: &str = "godot_cef/network/adblock_cosmetic_filtering";
+const SETTING_ADBLOCK_RESOURCES_PATH: &str = "godot_cef/network/adblock_resources_path";
+const SETTING_RESPONSE_FILTER_MAX_SIZE_MB: &str = "godot_cef/network/response_filter_max_size_mb";
+const SETTING_AUTH_CREDENTIALS: &str = "godot_cef/network/auth_credentials";
+const SETTING_RES_CACHE_CONTROL: &str = "godot_cef/schemes/res_cache_control";
+const SETTING_USER_CACHE_CONTROL: &str = "godot_cef/schemes/user_cache_control";
+const SETTING_SPA_FALLBACK_PREFIXES: &str = "godot_cef/schemes/spa_fallback_prefixes";
+const SETTING_CUSTOM_SCHEMES: &str = "godot_cef/schemes/custom_schemes";
+const SETTING_RESPONSE_HEADERS: &str = "godot_cef/schemes/response_headers";
+const SETTING_HEADER_PRESET: &str = "godot_cef/schemes/header_preset";
+const SETTING_USER_ALLOWED_ORIGINS: &str = "godot_cef/schemes/user_allowed_origins";
+const SETTING_USER_ALLOWED_PATHS: &str = "godot_cef/schemes/user_allowed_paths";
+const SETTING_USER_WRITABLE_PATHS: &str = "godot_cef/schemes/user_writable_paths";
+const SETTING_USER_WRITE_MAX_FILE_SIZE_MB: &str = "godot_cef/schemes/user_write_max_file_size_mb";
+const SETTING_USER_WRITE_QUOTA_MB: &str = "godot_cef/schemes/user_write_quota_mb";
+const SETTING_IMPORTED_RESOURCE_FALLBACK: &str = "godot_cef/schemes/imported_resource_fallback";
+const SETTING_IMPORTED_RESOURCE_CACHE_MB: &str = "godot_cef/schemes/imported_resource_cache_mb";
+const SETTING_DEV_SERVER_ROUTES: &str = "godot_cef/schemes/dev_server_routes";
+const SETTING_DEV_SERVER_REDIRECT_PATHS: &str = "godot_cef/schemes/dev_server_redirect_paths";
 const SETTING_CUSTOM_SWITCHES: &str = "godot_cef/advanced/custom_command_line_switches";
 
 const DEFAULT_DATA_PATH: &str = "user://cef-data";
 const DEFAULT_ALLOW_INSECURE_CONTENT: bool = false;
 const DEFAULT_IGNORE_CERTIFICATE_ERRORS: bool = false;
+const DEFAULT_CERTIFICATE_EXCEPTION_HOSTS: &str = ""; // Empty = no exceptions
 const DEFAULT_DISABLE_WEB_SECURITY: bool = false;
 const DEFAULT_PERMISSION_POLICY: i64 = crate::browser::permission_policy::DENY_ALL as i64;
 const DEFAULT_ENABLE_AUDIO_CAPTURE: bool = false;
@@ -34,6 +55,25 @@ const DEFAULT_PROXY_SERVER: &str = ""; // Empty = direct connection
 const DEFAULT_PROXY_BYPASS_LIST: &str = ""; // Empty = no bypass
 const DEFAULT_ENABLE_ADBLOCK: bool = false;
 const DEFAULT_ADBLOCK_RULES_PATH: &str = "";
+const DEFAULT_ADBLOCK_COSMETIC_FILTERING: bool = true;
+const DEFAULT_ADBLOCK_RESOURCES_PATH: &str = "";
+const DEFAULT_RESPONSE_FILTER_MAX_SIZE_MB: i64 = 8;
+const DEFAULT_AUTH_CREDENTIALS: &str = ""; // Empty = always emit `auth_requested`
+const DEFAULT_RES_CACHE_CONTROL: &str = "no-cache"; // Always revalidate with ETag
+const DEFAULT_USER_CACHE_CONTROL: &str = "no-cache";
+const DEFAULT_SPA_FALLBACK_PREFIXES: &str = ""; // Empty = no SPA fallback
+const DEFAULT_CUSTOM_SCHEMES: &str = ""; // Empty = only res:// and user://
+const DEFAULT_RESPONSE_HEADERS: &str = "* Access-Control-Allow-Origin: *";
+const DEFAULT_HEADER_PRESET: i64 = 0; // 0 = None, 1 = Cross-Origin Isolation
+const DEFAULT_USER_ALLOWED_ORIGINS: &str = "res://*"; // Only pages served from res://
+const DEFAULT_USER_ALLOWED_PATHS: &str = ""; // Empty = all of user://
+const DEFAULT_USER_WRITABLE_PATHS: &str = ""; // Empty = user:// is read-only
+const DEFAULT_USER_WRITE_MAX_FILE_SIZE_MB: i64 = 16; // 0 = no limit
+const DEFAULT_USER_WRITE_QUOTA_MB: i64 = 256; // 0 = no limit
+const DEFAULT_IMPORTED_RESOURCE_FALLBACK: bool = true;
+const DEFAULT_IMPORTED_RESOURCE_CACHE_MB: i64 = 64; // 0 = no caching
+const DEFAULT_DEV_SERVER_ROUTES: &str = ""; // Empty = always serve packed files
+const DEFAULT_DEV_SERVER_REDIRECT_PATHS: &str = "*/@vite/client";
 const DEFAULT_CUSTOM_SWITCHES: &str = ""; // Empty = no custom switches
 
 pub fn register_project_settings() {
@@ -59,6 +99,14 @@ pub fn register_project_settings() {
         DEFAULT_IGNORE_CERTIFICATE_ERRORS,
     );
 
+    register_string_setting(
+        &mut settings,
+        SETTING_CERTIFICATE_EXCEPTION_HOSTS,
+        DEFAULT_CERTIFICATE_EXCEPTION_HOSTS,
+        PropertyHint::PLACEHOLDER_TEXT,
+        "Comma-separated hosts, e.g., localhost:5173,*.dev.local",
+    );
+
     register_bool_setting(
         &mut settings,
         SETTING_DISABLE_WEB_SECURITY,
@@ -141,7 +189,156 @@ pub fn register_project_settings() {
         SETTING_ADBLOCK_RULES_PATH,
         DEFAULT_ADBLOCK_RULES_PATH,
         PropertyHint::PLACEHOLDER_TEXT,
-        "Path to EasyList/ABP rules file (empty = adblock disabled)",
+        "Comma-separated EasyList/ABP rule files (empty = adblock disabled)",
+    );
+
+    register_bool_setting(
+        &mut settings,
+        SETTING_ADBLOCK_COSMETIC_FILTERING,
+        DEFAULT_ADBLOCK_COSMETIC_FILTERING,
+    );
+
+    register_string_setting(
+        &mut settings,
+        SETTING_ADBLOCK_RESOURCES_PATH,
+        DEFAULT_ADBLOCK_RESOURCES_PATH,
+        PropertyHint::FILE,
+        "*.json",
+    );
+
+    register_int_setting(
+        &mut settings,
+        SETTING_RESPONSE_FILTER_MAX_SIZE_MB,
+        DEFAULT_RESPONSE_FILTER_MAX_SIZE_MB,
+        PropertyHint::RANGE,
+        "1,256,or_greater",
+    );
+
+    register_string_setting(
+        &mut settings,
+        SETTING_AUTH_CREDENTIALS,
+        DEFAULT_AUTH_CREDENTIALS,
+        PropertyHint::MULTILINE_TEXT,
+        "",
+    );
+
+    // Scheme handler settings
+    register_string_setting(
+        &mut settings,
+        SETTING_RES_CACHE_CONTROL,
+        DEFAULT_RES_CACHE_CONTROL,
+        PropertyHint::PLACEHOLDER_TEXT,
+        "Cache-Control for res:// (empty = header omitted)",
+    );
+
+    register_string_setting(
+        &mut settings,
+        SETTING_USER_CACHE_CONTROL,
+        DEFAULT_USER_CACHE_CONTROL,
+        PropertyHint::PLACEHOLDER_TEXT,
+        "Cache-Control for user:// (empty = header omitted)",
+    );
+
+    register_string_setting(
+        &mut settings,
+        SETTING_SPA_FALLBACK_PREFIXES,
+        DEFAULT_SPA_FALLBACK_PREFIXES,
+        PropertyHint::PLACEHOLDER_TEXT,
+        "Comma-separated list, e.g., res://ui/",
+    );
+
+    register_string_setting(
+        &mut settings,
+        SETTING_CUSTOM_SCHEMES,
+        DEFAULT_CUSTOM_SCHEMES,
+        PropertyHint::PLACEHOLDER_TEXT,
+        "Comma-separated list, e.g., game,api",
+    );
+
+    register_string_setting(
+        &mut settings,
+        SETTING_RESPONSE_HEADERS,
+        DEFAULT_RESPONSE_HEADERS,
+        PropertyHint::MULTILINE_TEXT,
+        "",
+    );
+
+    register_int_setting(
+        &mut settings,
+        SETTING_HEADER_PRESET,
+        DEFAULT_HEADER_PRESET,
+        PropertyHint::ENUM,
+        "None,Cross-Origin Isolation (COOP/COEP)",
+    );
+
+    register_string_setting(
+        &mut settings,
+        SETTING_USER_ALLOWED_ORIGINS,
+        DEFAULT_USER_ALLOWED_ORIGINS,
+        PropertyHint::PLACEHOLDER_TEXT,
+        "Comma-separated origin patterns, e.g., res://*,https://*.example.com",
+    );
+
+    register_string_setting(
+        &mut settings,
+        SETTING_USER_ALLOWED_PATHS,
+        DEFAULT_USER_ALLOWED_PATHS,
+        PropertyHint::PLACEHOLDER_TEXT,
+        "Comma-separated path patterns, e.g., user://ui-cache/* (empty = all)",
+    );
+
+    register_string_setting(
+        &mut settings,
+        SETTING_USER_WRITABLE_PATHS,
+        DEFAULT_USER_WRITABLE_PATHS,
+        PropertyHint::PLACEHOLDER_TEXT,
+        "Comma-separated directories, e.g., user://levels/ (empty = read-only)",
+    );
+
+    register_int_setting(
+        &mut settings,
+        SETTING_USER_WRITE_MAX_FILE_SIZE_MB,
+        DEFAULT_USER_WRITE_MAX_FILE_SIZE_MB,
+        PropertyHint::RANGE,
+        "0,1024,or_greater",
+    );
+
+    register_int_setting(
+        &mut settings,
+        SETTING_USER_WRITE_QUOTA_MB,
+        DEFAULT_USER_WRITE_QUOTA_MB,
+        PropertyHint::RANGE,
+        "0,10240,or_greater",
+    );
+
+    register_bool_setting(
+        &mut settings,
+        SETTING_IMPORTED_RESOURCE_FALLBACK,
+        DEFAULT_IMPORTED_RESOURCE_FALLBACK,
+    );
+
+    register_int_setting(
+        &mut settings,
+        SETTING_IMPORTED_RESOURCE_CACHE_MB,
+        DEFAULT_IMPORTED_RESOURCE_CACHE_MB,
+        PropertyHint::RANGE,
+        "0,1024,or_greater",
+    );
+
+    register_string_setting(
+        &mut settings,
+        SETTING_DEV_SERVER_ROUTES,
+        DEFAULT_DEV_SERVER_ROUTES,
+        PropertyHint::MULTILINE_TEXT,
+        "",
+    );
+
+    register_string_setting(
+        &mut settings,
+        SETTING_DEV_SERVER_REDIRECT_PATHS,
+        DEFAULT_DEV_SERVER_REDIRECT_PATHS,
+        PropertyHint::PLACEHOLDER_TEXT,
+        "Comma-separated upstream URL patterns, e.g., */@vite/client",
     );
 
     // Advanced settings
@@ -323,19 +520,196 @@ pub fn is_adblock_enabled() -> bool {
     get_setting_or(SETTING_ENABLE_ADBLOCK, DEFAULT_ENABLE_ADBLOCK)
 }
 
-/// Returns the globalized adblock rules file path, or `None` if unset.
-pub fn get_adblock_rules_path() -> Option<PathBuf> {
-    let raw = get_string_setting(SETTING_ADBLOCK_RULES_PATH, DEFAULT_ADBLOCK_RULES_PATH);
-    if raw.trim().is_empty() {
+/// Returns the globalized adblock rule file paths. Empty if unset.
+pub fn get_adblock_rules_paths() -> Vec<PathBuf> {
+    let settings = ProjectSettings::singleton();
+    get_list_setting(SETTING_ADBLOCK_RULES_PATH, DEFAULT_ADBLOCK_RULES_PATH)
+        .into_iter()
+        .map(|path| {
+            let absolute = settings.globalize_path(path.as_str()).to_string();
+            PathBuf::from(absolute)
+        })
+        .collect()
+}
+
+/// Whether element-hiding rules and scriptlets are applied to pages when adblock is enabled.
+pub fn is_adblock_cosmetic_filtering_enabled() -> bool {
+    get_setting_or(
+        SETTING_ADBLOCK_COSMETIC_FILTERING,
+        DEFAULT_ADBLOCK_COSMETIC_FILTERING,
+    )
+}
+
+/// Returns the globalized path of the adblock scriptlet resources file, `None` if unset.
+pub fn get_adblock_resources_path() -> Option<PathBuf> {
+    let path = get_string_setting(
+        SETTING_ADBLOCK_RESOURCES_PATH,
+        DEFAULT_ADBLOCK_RESOURCES_PATH,
+    );
+    let path = path.trim();
+    if path.is_empty() {
         return None;
     }
-
-    let settings = ProjectSettings::singleton();
-    let raw_gstring: GString = raw.as_str().into();
-    let absolute = settings.globalize_path(&raw_gstring).to_string();
+    let absolute = ProjectSettings::singleton()
+        .globalize_path(path)
+        .to_string();
     Some(PathBuf::from(absolute))
 }
 
+/// Returns the `Cache-Control` value for `res://` responses. Empty string means omit the header.
+pub fn get_res_cache_control() -> String {
+    get_string_setting(SETTING_RES_CACHE_CONTROL, DEFAULT_RES_CACHE_CONTROL)
+        .trim()
+        .to_string()
+}
+
+/// Returns the `Cache-Control` value for `user://` responses. Empty string means omit the header.
+pub fn get_user_cache_control() -> String {
+    get_string_setting(SETTING_USER_CACHE_CONTROL, DEFAULT_USER_CACHE_CONTROL)
+        .trim()
+        .to_string()
+}
+
+/// Returns the URL prefixes whose unknown routes are answered with the prefix's `index.html`.
+/// Each prefix is normalized to end with `/`.
+pub fn get_spa_fallback_prefixes() -> Vec<String> {
+    get_list_setting(SETTING_SPA_FALLBACK_PREFIXES, DEFAULT_SPA_FALLBACK_PREFIXES)
+        .into_iter()
+        .map(|prefix| {
+            if prefix.ends_with('/') {
+                prefix
+            } else {
+                format!("{}/", prefix)
+            }
+        })
+        .collect()
+}
+
+/// Returns the project-defined custom schemes, normalized and without reserved names.
+pub fn get_custom_schemes() -> Vec<String> {
+    let raw = get_string_setting(SETTING_CUSTOM_SCHEMES, DEFAULT_CUSTOM_SCHEMES);
+    cef_app::parse_custom_schemes(&raw)
+}
+
+/// Returns the raw response header rules for `res://` and `user://`, one rule per line.
+pub fn get_response_header_rules() -> String {
+    get_string_setting(SETTING_RESPONSE_HEADERS, DEFAULT_RESPONSE_HEADERS)
+}
+
+/// Returns the response header preset: 0 = None, 1 = Cross-Origin Isolation.
+pub fn get_header_preset() -> i64 {
+    get_setting_or(SETTING_HEADER_PRESET, DEFAULT_HEADER_PRESET)
+}
+
+/// Splits a comma- or newline-separated setting into trimmed, non-empty entries.
+fn get_list_setting(name: &str, default: &str) -> Vec<String> {
+    get_string_setting(name, default)
+        .split([',', '\n'])
+        .map(|entry| entry.trim())
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| entry.to_string())
+        .collect()
+}
+
+/// Returns the hosts whose certificate errors are allowed without asking the node.
+pub fn get_certificate_exception_hosts() -> Vec<String> {
+    get_list_setting(
+        SETTING_CERTIFICATE_EXCEPTION_HOSTS,
+        DEFAULT_CERTIFICATE_EXCEPTION_HOSTS,
+    )
+}
+
+/// Returns the origin patterns allowed to read `user://`.
+pub fn get_user_allowed_origins() -> Vec<String> {
+    get_list_setting(SETTING_USER_ALLOWED_ORIGINS, DEFAULT_USER_ALLOWED_ORIGINS)
+}
+
+/// Returns the `user://` path patterns that may be served. Empty means all paths.
+pub fn get_user_allowed_paths() -> Vec<String> {
+    get_list_setting(SETTING_USER_ALLOWED_PATHS, DEFAULT_USER_ALLOWED_PATHS)
+}
+
+/// Returns the `user://` directories that accept PUT/POST/DELETE, each ending with `/`.
+/// Entries outside `user://` are ignored.
+pub fn get_user_writable_paths() -> Vec<String> {
+    get_list_setting(SETTING_USER_WRITABLE_PATHS, DEFAULT_USER_WRITABLE_PATHS)
+        .into_iter()
+        .filter(|path| path.starts_with("user://") && path.len() > "user://".len())
+        .map(|path| {
+            if path.ends_with('/') {
+                path
+            } else {
+                format!("{}/", path)
+            }
+        })
+        .collect()
+}
+
+/// Returns the largest response body passed to response filters; larger bodies pass through unfiltered.
+pub fn get_response_filter_max_size_mb() -> u64 {
+    get_setting_or(
+        SETTING_RESPONSE_FILTER_MAX_SIZE_MB,
+        DEFAULT_RESPONSE_FILTER_MAX_SIZE_MB,
+    )
+    .max(1) as u64
+}
+
+/// Returns the credential presets (`user:password@host[:port]`), one per line.
+/// Lines are not split on commas, which passwords may contain. The presets are
+/// plain text in `project.godot` and ship with exported builds.
+pub fn get_auth_credentials() -> Vec<String> {
+    let raw = get_string_setting(SETTING_AUTH_CREDENTIALS, DEFAULT_AUTH_CREDENTIALS);
+    raw.lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| line.to_string())
+        .collect()
+}
+
+/// Returns the maximum size of a single file written to `user://`. Returns 0 for no limit.
+pub fn get_user_write_max_file_size_mb() -> u64 {
+    get_setting_or(
+        SETTING_USER_WRITE_MAX_FILE_SIZE_MB,
+        DEFAULT_USER_WRITE_MAX_FILE_SIZE_MB,
+    )
+    .max(0) as u64
+}
+
+/// Returns the total size allowed below each writable `user://` directory. Returns 0 for no limit.
+pub fn get_user_write_quota_mb() -> u64 {
+    get_setting_or(SETTING_USER_WRITE_QUOTA_MB, DEFAULT_USER_WRITE_QUOTA_MB).max(0) as u64
+}
+
+/// Whether missing `res://` files are re-encoded from their imported resources.
+pub fn is_imported_resource_fallback_enabled() -> bool {
+    get_setting_or(
+        SETTING_IMPORTED_RESOURCE_FALLBACK,
+        DEFAULT_IMPORTED_RESOURCE_FALLBACK,
+    )
+}
+
+/// Returns the memory budget for re-encoded imported resources. Returns 0 to disable caching.
+pub fn get_imported_resource_cache_mb() -> u64 {
+    get_setting_or(
+        SETTING_IMPORTED_RESOURCE_CACHE_MB,
+        DEFAULT_IMPORTED_RESOURCE_CACHE_MB,
+    )
+    .max(0) as u64
+}
+
+/// Returns the raw dev server routes (`<res:// prefix> <upstream URL>`), one per line.
+pub fn get_dev_server_routes() -> String {
+    get_string_setting(SETTING_DEV_SERVER_ROUTES, DEFAULT_DEV_SERVER_ROUTES)
+}
+
+/// Returns the upstream URL patterns that are redirected to rather than proxied.
+pub fn get_dev_server_redirect_paths() -> Vec<String> {
+    get_list_setting(
+        SETTING_DEV_SERVER_REDIRECT_PATHS,
+        DEFAULT_DEV_SERVER_REDIRECT_PATHS,
+    )
+}
+
 /// Returns custom command-line switches as a list of strings.
 /// Each line in the multiline string is treated as a separate switch.
 pub fn get_custom_switches() -> Vec<String> {
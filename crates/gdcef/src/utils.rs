@@ -218,6 +218,12 @@ fn get_executable_paths() -> CefResult<Vec<PathBuf>> {
     Ok(paths)
 }
 
+/// Whether this is an editor run or a debug build, where development-only
+/// features such as live reload and the dev server proxy are active.
+pub(crate) fn is_development_run() -> bool {
+    Os::singleton().is_debug_build() || Engine::singleton().is_editor_hint()
+}
+
 /// Determines if IPC inspector should be enabled.
 ///
 /// IPC inspector is only enabled when:
@@ -226,11 +232,5 @@ fn get_executable_paths() -> CefResult<Vec<PathBuf>> {
 ///
 /// This is a security measure to prevent remote debugging in production builds.
 pub(crate) fn should_enable_ipc_inspector() -> bool {
-    let os = Os::singleton();
-    let engine = Engine::singleton();
-
-    let is_debug_build = os.is_debug_build();
-    let is_editor_hint = engine.is_editor_hint();
-
-    is_debug_build || is_editor_hint
+    is_development_run()
 }
@@ -6,15 +6,18 @@ use std::sync::{Arc, Mutex};
 use wide::{i8x16, u8x16};
 
 use crate::accelerated_osr::PlatformAcceleratedRenderHandler;
+use crate::adblock_engine::AdblockEngineHandle;
 use crate::browser::{
     AudioPacket, AudioPacketQueue, AudioParamsState, AudioSampleRateState, AudioShutdownFlag,
-    AudioState, ConsoleMessageEvent, DownloadRequestEvent, DownloadUpdateEvent, DragDataInfo,
-    DragEvent, EventQueues, EventQueuesHandle, FindResultEvent, ImeCompositionRange,
-    LoadingStateEvent, PendingPermissionAggregates, PendingPermissionDecision,
+    AudioState, BlockedRequestEvent, ConsoleMessageEvent, DebugTimelineEvent, DebugTimelineKind,
+    DownloadRequestEvent, DownloadUpdateEvent, DragDataInfo, DragEvent, EventQueues,
+    EventQueuesHandle, FilteredResponseEvent, FindResultEvent, ImeCompositionRange,
+    InterceptedRequestEvent, InterceptedResponseSlot, LoadingStateEvent, PendingAuthRequests,
+    PendingCertificateErrors, PendingPermissionAggregates, PendingPermissionDecision,
     PendingPermissionRequests, PermissionPolicyFlag, PermissionRequestEvent,
-    PermissionRequestIdCounter,
+    PermissionRequestIdCounter, RequestHooks, ResourceLoadEvent,
 };
-use crate::utils::get_display_scale_factor;
+use crate::utils::{get_display_scale_factor, should_enable_ipc_inspector};
 
 macro_rules! impl_build_new {
     ($vis:vis $name:ident => $ret:ty; $($arg:ident : $arg_ty:ty),* $(,)?) => {
@@ -38,6 +41,29 @@ where
     }
 }
 
+/// Records an inspector timeline event. The event is only built when the
+/// inspector is available (debug builds and editor runs).
+fn record_debug_timeline<F>(queues: &mut EventQueues, build: F)
+where
+    F: FnOnce() -> DebugTimelineEvent,
+{
+    if should_enable_ipc_inspector() {
+        queues.debug_timeline_events.push_back(build());
+    }
+}
+
+/// Maps a raw `cef_log_severity_t` value to a label for the inspector timeline.
+fn console_level_label(level: u32) -> &'static str {
+    match level {
+        1 => "VERBOSE",
+        2 => "INFO",
+        3 => "WARNING",
+        4 => "ERROR",
+        5 => "FATAL",
+        _ => "LOG",
+    }
+}
+
 /// Bundles all the event queues and audio state used for browser-to-Godot communication.
 #[derive(Clone)]
 pub(crate) struct ClientQueues {
@@ -61,6 +87,14 @@ pub(crate) struct ClientQueues {
     pub pending_permission_requests: PendingPermissionRequests,
     /// Aggregated permission decision state keyed by callback token.
     pub pending_permission_aggregates: PendingPermissionAggregates,
+    /// URL patterns of the node's request interceptors.
+    pub request_hooks: RequestHooks,
+    /// Receives the browser when it is created asynchronously.
+    pub created_browser: CreatedBrowserSlot,
+    /// Certificate error callbacks keyed by request id.
+    pub pending_certificate_errors: PendingCertificateErrors,
+    /// Authentication callbacks keyed by request id.
+    pub pending_auth_requests: PendingAuthRequests,
 }
 
 impl ClientQueues {
@@ -71,6 +105,7 @@ impl ClientQueues {
         permission_request_counter: PermissionRequestIdCounter,
         pending_permission_requests: PendingPermissionRequests,
         pending_permission_aggregates: PendingPermissionAggregates,
+        request_hooks: RequestHooks,
     ) -> Self {
         use std::sync::atomic::AtomicBool;
         Self {
@@ -84,6 +119,10 @@ impl ClientQueues {
             permission_request_counter,
             pending_permission_requests,
             pending_permission_aggregates,
+            request_hooks,
+            created_browser: CreatedBrowserSlot::default(),
+            pending_certificate_errors: PendingCertificateErrors::default(),
+            pending_auth_requests: PendingAuthRequests::default(),
         }
     }
 
@@ -244,6 +283,122 @@ fn cef_request_to_adblock_request(
     )
 }
 
+/// Quotes `text` as a JavaScript string literal.
+fn js_string_literal(text: &str) -> String {
+    let mut literal = String::with_capacity(text.len() + 2);
+    literal.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => literal.push_str("\\\""),
+            '\\' => literal.push_str("\\\\"),
+            '\n' => literal.push_str("\\n"),
+            '\r' => literal.push_str("\\r"),
+            // Line separators end a string literal in older JavaScript engines
+            c if c.is_control() || c == '\u{2028}' || c == '\u{2029}' => {
+                literal.push_str(&format!("\\u{:04x}", c as u32));
+            }
+            c => literal.push(c),
+        }
+    }
+    literal.push('"');
+    literal
+}
+
+/// Builds the script hiding `hide_selectors`, or `None` when there is nothing to hide.
+/// Each selector gets its own rule so one the browser rejects does not void the others.
+fn cosmetic_filter_script(hide_selectors: &[String]) -> Option<String> {
+    if hide_selectors.is_empty() {
+        return None;
+    }
+    let css: String = hide_selectors
+        .iter()
+        .map(|selector| format!("{} {{ display: none !important; }}\n", selector))
+        .collect();
+    Some(format!(
+        r#"(() => {{
+  const style = document.createElement("style");
+  style.textContent = {};
+  const apply = () => (document.head || document.documentElement).appendChild(style);
+  if (document.documentElement) apply();
+  else document.addEventListener("readystatechange", apply, {{ once: true }});
+}})();
+"#,
+        js_string_literal(&css)
+    ))
+}
+
+/// URL of `frame` when cosmetic filters apply to it.
+fn cosmetic_filter_url(frame: &Frame) -> Option<String> {
+    let url = CefStringUtf16::from(&frame.url()).to_string();
+    (url.starts_with("http://") || url.starts_with("https://")).then_some(url)
+}
+
+fn hide_elements(frame: &Frame, url: &str, mut hide_selectors: Vec<String>) {
+    hide_selectors.sort();
+    if let Some(script) = cosmetic_filter_script(&hide_selectors) {
+        let code: CefStringUtf16 = script.as_str().into();
+        let script_url: CefStringUtf16 = url.into();
+        frame.execute_java_script(Some(&code), Some(&script_url), 0);
+    }
+}
+
+/// Hides elements the adblock rules define for the frame's URL and runs the
+/// scriptlets (`##+js`) they select from `godot_cef/network/adblock_resources_path`.
+fn inject_cosmetic_filters(adblock_engine: &AdblockEngineHandle, frame: &Frame) {
+    let Some(url) = cosmetic_filter_url(frame) else {
+        return;
+    };
+    let Ok(adblock_engine) = adblock_engine.read() else {
+        return;
+    };
+    let resources = adblock_engine.url_cosmetic_resources(&url);
+    drop(adblock_engine);
+
+    hide_elements(frame, &url, resources.hide_selectors.into_iter().collect());
+    if !resources.injected_script.trim().is_empty() {
+        let code: CefStringUtf16 = resources.injected_script.as_str().into();
+        let script_url: CefStringUtf16 = url.as_str().into();
+        frame.execute_java_script(Some(&code), Some(&script_url), 0);
+    }
+}
+
+/// Asks the renderer for the class names and ids in the loaded frame, answered
+/// by [`inject_generic_cosmetic_filters`].
+fn request_frame_class_ids(frame: &Frame) {
+    if cosmetic_filter_url(frame).is_none() {
+        return;
+    }
+    let route = CefStringUtf16::from(cef_app::ipc_contract::ROUTE_ADBLOCK_COLLECT_CLASS_IDS);
+    if let Some(mut message) = process_message_create(Some(&route)) {
+        frame.send_process_message(ProcessId::RENDERER, Some(&mut message));
+    }
+}
+
+/// Hides elements matched by generic `##.class` and `###id` rules among the
+/// class names and ids the renderer found in `frame`.
+pub(crate) fn inject_generic_cosmetic_filters(
+    adblock_engine: &AdblockEngineHandle,
+    frame: &Frame,
+    classes: &[String],
+    ids: &[String],
+) {
+    let Some(url) = cosmetic_filter_url(frame) else {
+        return;
+    };
+    let Ok(adblock_engine) = adblock_engine.read() else {
+        return;
+    };
+    let resources = adblock_engine.url_cosmetic_resources(&url);
+    if resources.generichide {
+        return;
+    }
+    let hide_selectors =
+        adblock_engine.hidden_class_id_selectors(classes, ids, &resources.exceptions);
+    drop(adblock_engine);
+
+    hide_elements(frame, &url, hide_selectors);
+}
+
 /// Common helper for start_dragging implementation.
 fn handle_start_dragging(
     drag_data: Option<&mut DragData>,
@@ -618,6 +773,14 @@ wrap_display_handler! {
             if let Some(url) = url {
                 let url_str = url.to_string();
                 with_event_queues(&self.event_queues, |queues| {
+                    record_debug_timeline(queues, || {
+                        DebugTimelineEvent::new(
+                            DebugTimelineKind::Navigation,
+                            "url_changed",
+                            url_str.clone(),
+                            String::new(),
+                        )
+                    });
                     queues.url_changes.push_back(url_str);
                 });
             }
@@ -649,6 +812,14 @@ wrap_display_handler! {
             let level: u32 = crate::cef_raw_to_u32!(level.get_raw());
 
             with_event_queues(&self.event_queues, |queues| {
+                record_debug_timeline(queues, || {
+                    DebugTimelineEvent::new(
+                        DebugTimelineKind::Console,
+                        "console_message",
+                        source_str.clone(),
+                        format!("[{}] {} (line {})", console_level_label(level), message_str, line),
+                    )
+                });
                 queues.console_messages.push_back(ConsoleMessageEvent {
                     level,
                     message: message_str,
@@ -689,13 +860,88 @@ wrap_context_menu_handler! {
 
 impl_build_new!(pub ContextMenuHandlerImpl => cef::ContextMenuHandler;);
 
+#[derive(Default)]
+enum CreatedBrowser {
+    #[default]
+    Idle,
+    Waiting,
+    Created(cef::Browser),
+    /// The creator gave up; the browser is closed as soon as it arrives.
+    Abandoned,
+}
+
+/// Hands a browser that CEF creates asynchronously (`show_dev_tools`) back to its creator.
+/// Only holds the browser between `on_after_created` and `take`, so a client never keeps
+/// a reference to its own browser.
+#[derive(Clone, Default)]
+pub(crate) struct CreatedBrowserSlot(Arc<Mutex<CreatedBrowser>>);
+
+impl CreatedBrowserSlot {
+    pub fn arm(&self) {
+        if let Ok(mut slot) = self.0.lock() {
+            *slot = CreatedBrowser::Waiting;
+        }
+    }
+
+    /// Stops waiting, closing the browser if it was created or once it is.
+    pub fn abandon(&self) {
+        let Ok(mut slot) = self.0.lock() else {
+            return;
+        };
+        match std::mem::take(&mut *slot) {
+            CreatedBrowser::Created(browser) => close_abandoned_browser(&browser),
+            CreatedBrowser::Waiting => *slot = CreatedBrowser::Abandoned,
+            CreatedBrowser::Idle | CreatedBrowser::Abandoned => {}
+        }
+    }
+
+    /// Takes the created browser, or returns `None` while still waiting.
+    pub fn take(&self) -> Option<cef::Browser> {
+        let mut slot = self.0.lock().ok()?;
+        match std::mem::take(&mut *slot) {
+            CreatedBrowser::Created(browser) => Some(browser),
+            other => {
+                *slot = other;
+                None
+            }
+        }
+    }
+
+    fn fill(&self, browser: &cef::Browser) {
+        let Ok(mut slot) = self.0.lock() else {
+            return;
+        };
+        match *slot {
+            CreatedBrowser::Waiting => *slot = CreatedBrowser::Created(browser.clone()),
+            CreatedBrowser::Abandoned => {
+                *slot = CreatedBrowser::Idle;
+                close_abandoned_browser(browser);
+            }
+            CreatedBrowser::Idle | CreatedBrowser::Created(_) => {}
+        }
+    }
+}
+
+fn close_abandoned_browser(browser: &cef::Browser) {
+    if let Some(host) = browser.host() {
+        host.close_browser(true as _);
+    }
+}
+
 wrap_life_span_handler! {
     pub(crate) struct LifeSpanHandlerImpl {
         event_queues: EventQueuesHandle,
         popup_policy: crate::browser::PopupPolicyFlag,
+        created_browser: CreatedBrowserSlot,
     }
 
     impl LifeSpanHandler {
+        fn on_after_created(&self, browser: Option<&mut Browser>) {
+            if let Some(browser) = browser {
+                self.created_browser.fill(browser);
+            }
+        }
+
         fn on_before_popup(
             &self,
             browser: Option<&mut Browser>,
@@ -720,6 +966,22 @@ wrap_life_span_handler! {
                 .map(|u| u.to_string())
                 .unwrap_or_default();
 
+            with_event_queues(&self.event_queues, |queues| {
+                record_debug_timeline(queues, || {
+                    let handling = match policy {
+                        popup_policy::REDIRECT => "redirected",
+                        popup_policy::SIGNAL_ONLY => "signaled",
+                        _ => "blocked",
+                    };
+                    DebugTimelineEvent::new(
+                        DebugTimelineKind::Popup,
+                        "popup_requested",
+                        url.clone(),
+                        format!("{} (user gesture: {})", handling, user_gesture != 0),
+                    )
+                });
+            });
+
             match policy {
                 popup_policy::REDIRECT => {
                     // Navigate the current browser to the popup URL
@@ -757,12 +1019,14 @@ wrap_life_span_handler! {
 impl_build_new!(
     pub LifeSpanHandlerImpl => cef::LifeSpanHandler;
     event_queues: EventQueuesHandle,
-    popup_policy: crate::browser::PopupPolicyFlag
+    popup_policy: crate::browser::PopupPolicyFlag,
+    created_browser: CreatedBrowserSlot
 );
 
 wrap_load_handler! {
     pub(crate) struct LoadHandlerImpl {
         event_queues: EventQueuesHandle,
+        adblock_engine: Option<AdblockEngineHandle>,
     }
 
     impl LoadHandler {
@@ -772,11 +1036,26 @@ wrap_load_handler! {
             frame: Option<&mut Frame>,
             _transition_type: TransitionType,
         ) {
+            if let Some(frame) = frame.as_deref()
+                && let Some(adblock_engine) = &self.adblock_engine
+                && crate::settings::is_adblock_cosmetic_filtering_enabled()
+            {
+                inject_cosmetic_filters(adblock_engine, frame);
+            }
+
             if let Some(frame) = frame
                 && frame.is_main() != 0
             {
                 let url = CefStringUtf16::from(&frame.url()).to_string();
                 with_event_queues(&self.event_queues, |queues| {
+                    record_debug_timeline(queues, || {
+                        DebugTimelineEvent::new(
+                            DebugTimelineKind::Navigation,
+                            "load_started",
+                            url.clone(),
+                            String::new(),
+                        )
+                    });
                     queues.loading_states.push_back(LoadingStateEvent::Started { url });
                 });
             }
@@ -788,11 +1067,26 @@ wrap_load_handler! {
             frame: Option<&mut Frame>,
             http_status_code: ::std::os::raw::c_int,
         ) {
+            if let Some(frame) = frame.as_deref()
+                && self.adblock_engine.is_some()
+                && crate::settings::is_adblock_cosmetic_filtering_enabled()
+            {
+                request_frame_class_ids(frame);
+            }
+
             if let Some(frame) = frame
                 && frame.is_main() != 0
             {
                 let url = CefStringUtf16::from(&frame.url()).to_string();
                 with_event_queues(&self.event_queues, |queues| {
+                    record_debug_timeline(queues, || {
+                        DebugTimelineEvent::new(
+                            DebugTimelineKind::Navigation,
+                            "load_finished",
+                            url.clone(),
+                            format!("HTTP {}", http_status_code),
+                        )
+                    });
                     queues.loading_states.push_back(LoadingStateEvent::Finished {
                         url,
                         http_status_code,
@@ -821,6 +1115,14 @@ wrap_load_handler! {
                 // Use the get_raw() method to safely convert Errorcode to i32
                 let error_code_i32: i32 = error_code.get_raw();
                 with_event_queues(&self.event_queues, |queues| {
+                    record_debug_timeline(queues, || {
+                        DebugTimelineEvent::new(
+                            DebugTimelineKind::Navigation,
+                            "load_error",
+                            url.clone(),
+                            format!("{} {}", error_code_i32, error_text),
+                        )
+                    });
                     queues.loading_states.push_back(LoadingStateEvent::Error {
                         url,
                         error_code: error_code_i32,
@@ -832,7 +1134,11 @@ wrap_load_handler! {
     }
 }
 
-impl_build_new!(pub LoadHandlerImpl => cef::LoadHandler; event_queues: EventQueuesHandle);
+impl_build_new!(
+    pub LoadHandlerImpl => cef::LoadHandler;
+    event_queues: EventQueuesHandle,
+    adblock_engine: Option<AdblockEngineHandle>
+);
 
 wrap_find_handler! {
     pub(crate) struct FindHandlerImpl {
@@ -1085,9 +1391,119 @@ impl_build_new!(pub DownloadHandlerImpl => cef::DownloadHandler; event_queues: E
 wrap_request_handler! {
     pub(crate) struct RequestHandlerImpl {
         event_queues: EventQueuesHandle,
+        adblock_engine: Option<AdblockEngineHandle>,
+        request_hooks: RequestHooks,
+        pending_certificate_errors: PendingCertificateErrors,
+        pending_auth_requests: PendingAuthRequests,
+        tried_auth_presets: crate::auth::TriedAuthPresets,
+        settings: RequestHandlerSettings,
     }
 
     impl RequestHandler {
+        fn auth_credentials(
+            &self,
+            _browser: Option<&mut Browser>,
+            _origin_url: Option<&CefString>,
+            is_proxy: ::std::os::raw::c_int,
+            host: Option<&CefString>,
+            port: ::std::os::raw::c_int,
+            realm: Option<&CefString>,
+            scheme: Option<&CefString>,
+            callback: Option<&mut AuthCallback>,
+        ) -> ::std::os::raw::c_int {
+            let Some(callback) = callback else {
+                return false as _;
+            };
+            let host = host.map(|host| host.to_string()).unwrap_or_default();
+            let realm = realm.map(|realm| realm.to_string()).unwrap_or_default();
+
+            let preset_key = crate::auth::preset_attempt_key(is_proxy != 0, &host, port, &realm);
+            if let Some((username, password)) = crate::auth::preset_credentials(&host, port)
+                && self
+                    .tried_auth_presets
+                    .lock()
+                    .is_ok_and(|mut tried| tried.insert(preset_key))
+            {
+                callback.cont(
+                    Some(&CefString::from(username.as_str())),
+                    Some(&CefString::from(password.as_str())),
+                );
+                return true as _;
+            }
+
+            let Some(request_id) = self.pending_auth_requests.insert(callback.clone()) else {
+                return false as _;
+            };
+            let event = crate::auth::AuthRequestEvent {
+                request_id,
+                host,
+                port,
+                realm,
+                scheme: scheme.map(|scheme| scheme.to_string()).unwrap_or_default(),
+                is_proxy: is_proxy != 0,
+            };
+            with_event_queues(&self.event_queues, |queues| {
+                queues.auth_requests.push_back(event);
+            });
+            true as _
+        }
+
+        fn on_certificate_error(
+            &self,
+            _browser: Option<&mut Browser>,
+            cert_error: Errorcode,
+            request_url: Option<&CefString>,
+            ssl_info: Option<&mut Sslinfo>,
+            callback: Option<&mut Callback>,
+        ) -> ::std::os::raw::c_int {
+            let Some(callback) = callback else {
+                return false as _;
+            };
+            let url = request_url.map(|url| url.to_string()).unwrap_or_default();
+            if crate::certificate::is_exception(&url) {
+                callback.cont();
+                return true as _;
+            }
+
+            let Some(request_id) = self.pending_certificate_errors.insert(callback.clone()) else {
+                return false as _;
+            };
+            let event = crate::certificate::CertificateErrorEvent {
+                request_id,
+                url,
+                error_code: cert_error.get_raw(),
+                info: ssl_info
+                    .as_deref()
+                    .map(crate::certificate::CertificateInfo::from_cef)
+                    .unwrap_or_default(),
+            };
+            with_event_queues(&self.event_queues, |queues| {
+                queues.certificate_errors.push_back(event);
+            });
+            true as _
+        }
+
+        fn resource_request_handler(
+            &self,
+            _browser: Option<&mut Browser>,
+            _frame: Option<&mut Frame>,
+            _request: Option<&mut Request>,
+            _is_navigation: ::std::os::raw::c_int,
+            _is_download: ::std::os::raw::c_int,
+            _request_initiator: Option<&CefString>,
+            _disable_default_handling: Option<&mut ::std::os::raw::c_int>,
+        ) -> Option<cef::ResourceRequestHandler> {
+            Some(ResourceRequestHandlerImpl::build(OsrResourceRequestHandler {
+                adblock_engine: self.adblock_engine.clone(),
+                event_queues: Some(self.event_queues.clone()),
+                request_hooks: Some(self.request_hooks.clone()),
+                tried_auth_presets: Some(self.tried_auth_presets.clone()),
+                intercepted_response: InterceptedResponseSlot::default(),
+                started_at: std::time::Instant::now(),
+                settings: self.settings,
+            }))
+        }
+
         fn on_render_process_terminated(
             &self,
             _browser: Option<&mut Browser>,
@@ -1110,7 +1526,36 @@ wrap_request_handler! {
     }
 }
 
-impl_build_new!(pub RequestHandlerImpl => cef::RequestHandler; event_queues: EventQueuesHandle);
+impl_build_new!(
+    pub RequestHandlerImpl => cef::RequestHandler;
+    event_queues: EventQueuesHandle,
+    adblock_engine: Option<AdblockEngineHandle>,
+    request_hooks: RequestHooks,
+    pending_certificate_errors: PendingCertificateErrors,
+    pending_auth_requests: PendingAuthRequests,
+    tried_auth_presets: crate::auth::TriedAuthPresets,
+    settings: RequestHandlerSettings
+);
+
+/// Settings the request handlers use on the IO thread, read on the main thread
+/// when the browser's handlers are built.
+#[derive(Clone, Copy, Default)]
+pub(crate) struct RequestHandlerSettings {
+    /// Whether loads are recorded on the inspector timeline.
+    record_timeline: bool,
+    /// Largest body passed to response filters, in bytes.
+    response_filter_max_size: usize,
+}
+
+impl RequestHandlerSettings {
+    fn read() -> Self {
+        Self {
+            record_timeline: should_enable_ipc_inspector(),
+            response_filter_max_size: crate::settings::get_response_filter_max_size_mb()
+                .saturating_mul(1024 * 1024) as usize,
+        }
+    }
+}
 
 fn push_permission_request(
     event_queues: &EventQueuesHandle,
@@ -1131,6 +1576,14 @@ fn push_permission_request(
     }
 
     let queued = with_event_queues(event_queues, |queues| {
+        record_debug_timeline(queues, || {
+            DebugTimelineEvent::new(
+                DebugTimelineKind::Permission,
+                "permission_requested",
+                url.clone(),
+                format!("{} (request id {})", permission_type, request_id),
+            )
+        });
         queues
             .permission_requests
             .push_back(PermissionRequestEvent {
@@ -1144,6 +1597,33 @@ fn push_permission_request(
     }
 }
 
+/// Records a permission request that was resolved by the default policy
+/// without reaching GDScript.
+fn record_permission_policy_decision(
+    event_queues: &EventQueuesHandle,
+    permission_types: &[&'static str],
+    requesting_origin: Option<&CefString>,
+    granted: bool,
+) {
+    with_event_queues(event_queues, |queues| {
+        record_debug_timeline(queues, || {
+            let url = requesting_origin
+                .map(|origin| origin.to_string())
+                .unwrap_or_default();
+            DebugTimelineEvent::new(
+                DebugTimelineKind::Permission,
+                "permission_requested",
+                url,
+                format!(
+                    "{} ({} by default policy)",
+                    permission_types.join(", "),
+                    if granted { "granted" } else { "denied" }
+                ),
+            )
+        });
+    });
+}
+
 fn map_permission_bits(
     requested: u32,
     mappings: &[(u32, &'static str)],
@@ -1296,13 +1776,23 @@ wrap_permission_handler! {
             let callback = callback.clone();
             let policy = self.permission_policy.load(Ordering::Relaxed);
 
-            if policy == permission_policy::ALLOW_ALL {
-                callback.cont(requested_permissions);
-                return true as _;
-            }
-
-            if policy == permission_policy::DENY_ALL {
-                callback.cont(media_permission_to_u32(cef::MediaAccessPermissionTypes::NONE));
+            if policy == permission_policy::ALLOW_ALL || policy == permission_policy::DENY_ALL {
+                let granted = policy == permission_policy::ALLOW_ALL;
+                let labels: Vec<&'static str> = map_media_permission_types(requested_permissions)
+                    .into_iter()
+                    .map(|(_, label)| label)
+                    .collect();
+                record_permission_policy_decision(
+                    &self.event_queues,
+                    &labels,
+                    requesting_origin,
+                    granted,
+                );
+                if granted {
+                    callback.cont(requested_permissions);
+                } else {
+                    callback.cont(media_permission_to_u32(cef::MediaAccessPermissionTypes::NONE));
+                }
                 return true as _;
             }
 
@@ -1346,13 +1836,19 @@ wrap_permission_handler! {
             let callback = callback.clone();
             let policy = self.permission_policy.load(Ordering::Relaxed);
 
-            if policy == permission_policy::ALLOW_ALL {
-                callback.cont(cef::PermissionRequestResult::ACCEPT);
-                return true as _;
-            }
-
-            if policy == permission_policy::DENY_ALL {
-                callback.cont(cef::PermissionRequestResult::DENY);
+            if policy == permission_policy::ALLOW_ALL || policy == permission_policy::DENY_ALL {
+                let granted = policy == permission_policy::ALLOW_ALL;
+                record_permission_policy_decision(
+                    &self.event_queues,
+                    &map_prompt_permission_types(requested_permissions),
+                    requesting_origin,
+                    granted,
+                );
+                callback.cont(if granted {
+                    cef::PermissionRequestResult::ACCEPT
+                } else {
+                    cef::PermissionRequestResult::DENY
+                });
                 return true as _;
             }
 
@@ -1439,11 +1935,17 @@ pub(crate) struct ClientHandlers {
 #[derive(Clone)]
 pub(crate) struct ClientIpcQueues {
     pub event_queues: EventQueuesHandle,
+    /// Matches the class names and ids reported by the renderer against generic rules.
+    pub adblock_engine: Option<AdblockEngineHandle>,
 }
 
-fn build_ipc_queues(queues: &ClientQueues) -> ClientIpcQueues {
+fn build_ipc_queues(
+    queues: &ClientQueues,
+    adblock_engine: Option<AdblockEngineHandle>,
+) -> ClientIpcQueues {
     ClientIpcQueues {
         event_queues: queues.event_queues.clone(),
+        adblock_engine,
     }
 }
 
@@ -1501,11 +2003,11 @@ wrap_client! {
         fn on_process_message_received(
             &self,
             _browser: Option<&mut cef::Browser>,
-            _frame: Option<&mut cef::Frame>,
+            frame: Option<&mut cef::Frame>,
             _source_process: ProcessId,
             message: Option<&mut ProcessMessage>,
         ) -> i32 {
-            crate::webrender_ipc::on_process_message_received(message, &self.ipc)
+            crate::webrender_ipc::on_process_message_received(frame, message, &self.ipc)
         }
     }
 }
@@ -1515,6 +2017,7 @@ fn build_client_handlers(
     cursor_type: Arc<Mutex<CursorType>>,
     queues: &ClientQueues,
     popup_policy: crate::browser::PopupPolicyFlag,
+    adblock_engine: Option<AdblockEngineHandle>,
 ) -> ClientHandlers {
     let audio_handler = if queues.enable_audio_capture {
         Some(AudioHandlerImpl::build(
@@ -1531,13 +2034,26 @@ fn build_client_handlers(
         render_handler,
         display_handler: DisplayHandlerImpl::build(cursor_type, queues.event_queues.clone()),
         context_menu_handler: ContextMenuHandlerImpl::build(),
-        life_span_handler: LifeSpanHandlerImpl::build(queues.event_queues.clone(), popup_policy),
-        load_handler: LoadHandlerImpl::build(queues.event_queues.clone()),
+        life_span_handler: LifeSpanHandlerImpl::build(
+            queues.event_queues.clone(),
+            popup_policy,
+            queues.created_browser.clone(),
+        ),
+        load_handler: LoadHandlerImpl::build(queues.event_queues.clone(), adblock_engine.clone()),
         find_handler: FindHandlerImpl::build(queues.event_queues.clone()),
         drag_handler: DragHandlerImpl::build(queues.event_queues.clone()),
         audio_handler,
         download_handler: DownloadHandlerImpl::build(queues.event_queues.clone()),
-        request_handler: RequestHandlerImpl::build(queues.event_queues.clone()),
+        request_handler: RequestHandlerImpl::build(
+            queues.event_queues.clone(),
+            adblock_engine,
+            queues.request_hooks.clone(),
+            queues.pending_certificate_errors.clone(),
+            queues.pending_auth_requests.clone(),
+            Default::default(),
+            // Read here because the request handlers run on the IO thread
+            RequestHandlerSettings::read(),
+        ),
         permission_handler: PermissionHandlerImpl::build(
             queues.event_queues.clone(),
             queues.pending_permission_requests.clone(),
@@ -1558,15 +2074,20 @@ impl CefClientImpl {
         cursor_type: Arc<Mutex<CursorType>>,
         queues: ClientQueues,
         popup_policy: crate::browser::PopupPolicyFlag,
+        adblock_engine: Option<AdblockEngineHandle>,
     ) -> cef::Client {
-        let ipc = build_ipc_queues(&queues);
-        let handlers = build_client_handlers(render_handler, cursor_type, &queues, popup_policy);
+        let ipc = build_ipc_queues(&queues, adblock_engine.clone());
+        let handlers = build_client_handlers(
+            render_handler,
+            cursor_type,
+            &queues,
+            popup_policy,
+            adblock_engine,
+        );
         Self::new(handlers, ipc)
     }
 }
 
-type AdblockEngineHandle = std::rc::Rc<adblock::Engine>;
-
 #[derive(Clone)]
 pub struct OsrRequestContextHandler {
     pub adblock_engine: Option<AdblockEngineHandle>,
@@ -1581,6 +2102,198 @@ impl OsrRequestContextHandler {
 #[derive(Clone)]
 pub struct OsrResourceRequestHandler {
     adblock_engine: Option<AdblockEngineHandle>,
+    /// Per-browser event queues. `None` for requests that are not associated
+    /// with a browser (handled through the request context).
+    event_queues: Option<EventQueuesHandle>,
+    /// The node's request interceptors and response filters, `None` like `event_queues`.
+    request_hooks: Option<RequestHooks>,
+    /// Credential presets sent by the browser's request handler, `None` like `event_queues`.
+    tried_auth_presets: Option<crate::auth::TriedAuthPresets>,
+    /// Response decided by an interceptor for this request, served by `resource_handler`.
+    intercepted_response: InterceptedResponseSlot,
+    /// When CEF asked for this request's handler, used for `duration_ms`.
+    started_at: std::time::Instant,
+    /// Settings of the browser's request handler; defaults for the request context.
+    settings: RequestHandlerSettings,
+}
+
+impl OsrResourceRequestHandler {
+    fn queue_request_blocked(&self, request: &cef::Request, rule: Option<String>) {
+        let Some(event_queues) = &self.event_queues else {
+            return;
+        };
+        let event = BlockedRequestEvent {
+            url: CefStringUtf16::from(&request.url()).to_string(),
+            rule: rule.unwrap_or_default(),
+        };
+        with_event_queues(event_queues, |queues| {
+            queues.blocked_requests.push_back(event);
+        });
+    }
+
+    /// Event queues of a node that enabled resource load events.
+    fn resource_event_queues(&self) -> Option<&EventQueuesHandle> {
+        if !resource_events_enabled(self.request_hooks.as_ref()) {
+            return None;
+        }
+        self.event_queues.as_ref()
+    }
+
+    fn queue_resource_load_started(&self, request: &cef::Request) {
+        let Some(event_queues) = self.resource_event_queues() else {
+            return;
+        };
+        let event = resource_load_started_event(
+            request.identifier(),
+            CefStringUtf16::from(&request.url()).to_string(),
+            cef_resource_type_to_adblock_request_type(request.resource_type()),
+            CefStringUtf16::from(&request.method()).to_string(),
+        );
+        with_event_queues(event_queues, |queues| {
+            queues.resource_loads.push_back(event);
+        });
+    }
+
+    fn queue_resource_load_completed(
+        &self,
+        request: &cef::Request,
+        response: Option<&cef::Response>,
+        status: UrlrequestStatus,
+        received_bytes: i64,
+    ) {
+        let Some(event_queues) = self.resource_event_queues() else {
+            return;
+        };
+        let event = resource_load_completed_event(
+            request.identifier(),
+            CefStringUtf16::from(&request.url()).to_string(),
+            response.map(|r| (r.status(), CefStringUtf16::from(&r.mime_type()).to_string())),
+            status,
+            received_bytes,
+            self.started_at.elapsed(),
+        );
+        with_event_queues(event_queues, |queues| {
+            queues.resource_loads.push_back(event);
+        });
+    }
+
+    fn forget_accepted_auth_presets(
+        &self,
+        request: &cef::Request,
+        response: Option<&cef::Response>,
+    ) {
+        let (Some(tried), Some(response)) = (&self.tried_auth_presets, response) else {
+            return;
+        };
+        let url = CefStringUtf16::from(&request.url()).to_string();
+        let Some((host, port)) = url::Url::parse(&url)
+            .ok()
+            .and_then(|url| Some((url.host_str()?.to_string(), url.port_or_known_default()?)))
+        else {
+            return;
+        };
+        if let Ok(mut tried) = tried.lock()
+            && !tried.is_empty()
+        {
+            crate::auth::forget_accepted_presets(
+                &mut tried,
+                &host,
+                i32::from(port),
+                response.status(),
+            );
+        }
+    }
+
+    /// Hands `request` to the node's request interceptors when its URL matches one
+    /// of their patterns. Returns `false` when no interceptor applies.
+    fn queue_intercepted_request(&self, request: &cef::Request, callback: &cef::Callback) -> bool {
+        let (Some(event_queues), Some(hooks)) = (&self.event_queues, &self.request_hooks) else {
+            return false;
+        };
+
+        let url = CefStringUtf16::from(&request.url()).to_string();
+        let matched = hooks.interceptor_patterns.read().is_ok_and(|patterns| {
+            patterns
+                .iter()
+                .any(|pattern| crate::godot_protocol::glob_matches(pattern, &url))
+        });
+        if !matched {
+            return false;
+        }
+
+        let event = InterceptedRequestEvent {
+            method: CefStringUtf16::from(&request.method()).to_string(),
+            url,
+            headers: crate::godot_protocol::read_request_headers(request),
+            body: crate::godot_protocol::read_request_body(request),
+            response: self.intercepted_response.clone(),
+            callback: callback.clone(),
+        };
+        with_event_queues(event_queues, |queues| {
+            queues.intercepted_requests.push_back(event);
+        })
+    }
+
+    /// Returns a handler that fetches `request` itself when its URL matches one
+    /// of the node's response filters, so the body can be filtered on the main
+    /// thread before it is served.
+    fn filtered_resource_handler(
+        &self,
+        browser: Option<&cef::Browser>,
+        request: &cef::Request,
+    ) -> Option<cef::ResourceHandler> {
+        use crate::godot_protocol::{
+            CustomSchemeResourceHandler, CustomSchemeResourceHandlerImpl, FetchedResponseHook,
+        };
+        let (Some(event_queues), Some(hooks)) = (&self.event_queues, &self.request_hooks) else {
+            return None;
+        };
+
+        // The MIME type is only known once the response arrives
+        let url = CefStringUtf16::from(&request.url()).to_string();
+        let matched = hooks.response_filters.read().is_ok_and(|filters| {
+            filters
+                .iter()
+                .any(|filter| crate::godot_protocol::glob_matches(&filter.url_pattern, &url))
+        });
+        if !matched {
+            return None;
+        }
+
+        let context = browser
+            .and_then(|browser| browser.host())
+            .and_then(|host| host.request_context());
+        let filters = hooks.response_filters.clone();
+        let event_queues = event_queues.clone();
+        let max_body_size = self.settings.response_filter_max_size;
+        let on_fetched: FetchedResponseHook = Arc::new(move |response, deferred| {
+            let mime_type = response.mime_type().to_ascii_lowercase();
+            let matched = filters.read().is_ok_and(|filters| {
+                filters
+                    .iter()
+                    .any(|filter| filter.matches(&url, &mime_type))
+            });
+            if !matched {
+                deferred.complete(response);
+                return;
+            }
+            if response.body.len() > max_body_size {
+                godot::global::godot_warn!(
+                    "[ResponseFilter] {} exceeds the response filter size limit and is passed through unfiltered",
+                    url
+                );
+                deferred.complete(response);
+                return;
+            }
+            let event = FilteredResponseEvent::new(url.clone(), mime_type, response, deferred);
+            with_event_queues(&event_queues, |queues| {
+                queues.filtered_responses.push_back(event);
+            });
+        });
+        Some(CustomSchemeResourceHandlerImpl::build(
+            CustomSchemeResourceHandler::fetched(context, on_fetched),
+        ))
+    }
 }
 
 wrap_resource_request_handler! {
@@ -1594,14 +2307,21 @@ wrap_resource_request_handler! {
             _browser: Option<&mut cef::Browser>,
             _frame: Option<&mut cef::Frame>,
             request: Option<&mut cef::Request>,
-            _callback: Option<&mut cef::Callback>,
+            callback: Option<&mut cef::Callback>,
         ) -> ReturnValue {
+            let Some(request) = request else {
+                return ReturnValue::CONTINUE;
+            };
+            self.handler.queue_resource_load_started(request);
+
             if let Some(adblock_engine) = &self.handler.adblock_engine
-                && let Some(request) = request
+                && let Ok(adblock_engine) = adblock_engine.read()
             {
                 match cef_request_to_adblock_request(request) {
                     Ok(adblock_request) => {
-                        if adblock_engine.check_network_request(&adblock_request).matched {
+                        let result = adblock_engine.check_network_request(&adblock_request);
+                        if result.matched {
+                            self.handler.queue_request_blocked(request, result.filter);
                             return ReturnValue::CANCEL;
                         }
                     }
@@ -1614,12 +2334,80 @@ wrap_resource_request_handler! {
                 }
             }
 
+            // Interceptors run on the main thread; the request resumes once they decide
+            if let Some(callback) = callback
+                && self.handler.queue_intercepted_request(request, callback)
+            {
+                return ReturnValue::CONTINUE_ASYNC;
+            }
+
             ReturnValue::CONTINUE
         }
 
+        fn resource_handler(
+            &self,
+            browser: Option<&mut cef::Browser>,
+            _frame: Option<&mut cef::Frame>,
+            request: Option<&mut cef::Request>,
+        ) -> Option<cef::ResourceHandler> {
+            // Taken so a redirect followed from here is intercepted afresh
+            let response = self.handler.intercepted_response.lock().ok().and_then(|mut slot| slot.take());
+            if let Some(response) = response {
+                return Some(crate::godot_protocol::CustomSchemeResourceHandlerImpl::build(
+                    crate::godot_protocol::CustomSchemeResourceHandler::immediate(response),
+                ));
+            }
+            self.handler.filtered_resource_handler(browser.as_deref(), request?)
+        }
 
-
-
+        fn on_resource_load_complete(
+            &self,
+            _browser: Option<&mut cef::Browser>,
+            _frame: Option<&mut cef::Frame>,
+            request: Option<&mut cef::Request>,
+            response: Option<&mut cef::Response>,
+            status: UrlrequestStatus,
+            received_content_length: i64,
+        ) {
+            let (Some(event_queues), Some(request)) = (&self.handler.event_queues, request) else {
+                return;
+            };
+            self.handler.forget_accepted_auth_presets(request, response.as_deref());
+            self.handler.queue_resource_load_completed(
+                request,
+                response.as_deref(),
+                status,
+                received_content_length,
+            );
+
+            if !self.handler.settings.record_timeline {
+                return;
+            }
+            with_event_queues(event_queues, |queues| {
+                queues.debug_timeline_events.push_back({
+                    let url = CefStringUtf16::from(&request.url()).to_string();
+                    let method = CefStringUtf16::from(&request.method()).to_string();
+                    let resource_type =
+                        cef_resource_type_to_adblock_request_type(request.resource_type());
+                    let http_status = response.as_ref().map(|r| r.status()).unwrap_or(0);
+                    let outcome = match status {
+                        UrlrequestStatus::SUCCESS => format!("{}", http_status),
+                        UrlrequestStatus::CANCELED => "canceled".to_string(),
+                        UrlrequestStatus::FAILED => "failed".to_string(),
+                        _ => "unknown".to_string(),
+                    };
+                    DebugTimelineEvent::new(
+                        DebugTimelineKind::Network,
+                        "resource_load",
+                        url,
+                        format!(
+                            "{} {} {} · {} B",
+                            method, outcome, resource_type, received_content_length
+                        ),
+                    )
+                });
+            });
+        }
     }
 }
 
@@ -1646,6 +2434,12 @@ wrap_request_context_handler! {
         ) -> Option<cef::ResourceRequestHandler> {
             Some(ResourceRequestHandlerImpl::build(OsrResourceRequestHandler {
                 adblock_engine: self.handler.adblock_engine.clone(),
+                event_queues: None,
+                request_hooks: None,
+                tried_auth_presets: None,
+                intercepted_response: InterceptedResponseSlot::default(),
+                started_at: std::time::Instant::now(),
+                settings: RequestHandlerSettings::default(),
             }))
         }
     }
@@ -1656,10 +2450,163 @@ impl_build_new!(
     handler: OsrRequestContextHandler
 );
 
+/// Whether the node behind `hooks` asked for resource load events.
+fn resource_events_enabled(hooks: Option<&RequestHooks>) -> bool {
+    hooks.is_some_and(|hooks| {
+        hooks
+            .resource_events
+            .load(std::sync::atomic::Ordering::Relaxed)
+    })
+}
+
+fn resource_load_started_event(
+    id: u64,
+    url: String,
+    resource_type: &'static str,
+    method: String,
+) -> ResourceLoadEvent {
+    ResourceLoadEvent::Started {
+        id,
+        url,
+        resource_type,
+        method,
+    }
+}
+
+/// `response` is the HTTP status and MIME type, `None` when nothing was received.
+fn resource_load_completed_event(
+    id: u64,
+    url: String,
+    response: Option<(i32, String)>,
+    status: UrlrequestStatus,
+    received_bytes: i64,
+    elapsed: std::time::Duration,
+) -> ResourceLoadEvent {
+    let error = match status {
+        UrlrequestStatus::SUCCESS => "",
+        UrlrequestStatus::CANCELED => "canceled",
+        _ => "failed",
+    };
+    let (status, mime_type) = response.unwrap_or_default();
+    ResourceLoadEvent::Completed {
+        id,
+        url,
+        status,
+        mime_type,
+        received_bytes,
+        duration_ms: elapsed.as_millis().try_into().unwrap_or(i64::MAX),
+        error: error.to_string(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn console_level_label_maps_known_severities() {
+        assert_eq!(console_level_label(2), "INFO");
+        assert_eq!(console_level_label(3), "WARNING");
+        assert_eq!(console_level_label(4), "ERROR");
+        assert_eq!(console_level_label(0), "LOG");
+        assert_eq!(console_level_label(99), "LOG");
+    }
+
+    #[test]
+    fn js_string_literal_escapes_quotes_and_line_breaks() {
+        assert_eq!(js_string_literal("a\"b\\c"), r#""a\"b\\c""#);
+        assert_eq!(js_string_literal("x\ny\u{2028}"), r#""x\ny\u2028""#);
+    }
+
+    #[test]
+    fn cosmetic_filter_script_hides_each_selector() {
+        let selectors = vec![".ad".to_string(), "#banner".to_string()];
+        let script = cosmetic_filter_script(&selectors).unwrap_or_default();
+        assert!(script.contains(r#".ad { display: none !important; }\n#banner"#));
+        assert_eq!(cosmetic_filter_script(&[]), None);
+    }
+
+    #[test]
+    fn resource_events_follow_the_node_toggle() {
+        let hooks = RequestHooks::default();
+        assert!(!resource_events_enabled(None));
+        assert!(!resource_events_enabled(Some(&hooks)));
+        hooks
+            .resource_events
+            .store(true, std::sync::atomic::Ordering::Relaxed);
+        assert!(resource_events_enabled(Some(&hooks)));
+    }
+
+    #[test]
+    fn resource_load_started_event_keeps_request_fields() {
+        assert_eq!(
+            resource_load_started_event(
+                7,
+                "https://a.test/".to_string(),
+                "document",
+                "GET".to_string()
+            ),
+            ResourceLoadEvent::Started {
+                id: 7,
+                url: "https://a.test/".to_string(),
+                resource_type: "document",
+                method: "GET".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn resource_load_completed_event_reports_status_and_duration() {
+        let event = resource_load_completed_event(
+            3,
+            "https://a.test/app.js".to_string(),
+            Some((200, "text/javascript".to_string())),
+            UrlrequestStatus::SUCCESS,
+            1024,
+            std::time::Duration::from_micros(12_900),
+        );
+        assert_eq!(
+            event,
+            ResourceLoadEvent::Completed {
+                id: 3,
+                url: "https://a.test/app.js".to_string(),
+                status: 200,
+                mime_type: "text/javascript".to_string(),
+                received_bytes: 1024,
+                duration_ms: 12,
+                error: String::new(),
+            }
+        );
+    }
+
+    #[test]
+    fn resource_load_completed_event_reports_errors() {
+        for (status, error) in [
+            (UrlrequestStatus::CANCELED, "canceled"),
+            (UrlrequestStatus::FAILED, "failed"),
+        ] {
+            assert_eq!(
+                resource_load_completed_event(
+                    1,
+                    String::new(),
+                    None,
+                    status,
+                    0,
+                    std::time::Duration::MAX,
+                ),
+                ResourceLoadEvent::Completed {
+                    id: 1,
+                    url: String::new(),
+                    status: 0,
+                    mime_type: String::new(),
+                    received_bytes: 0,
+                    duration_ms: i64::MAX,
+                    error: error.to_string(),
+                }
+            );
+        }
+    }
+
     #[test]
     fn test_bgra_to_rgba_single_pixel() {
         // BGRA: B=10, G=20, R=30, A=255 → RGBA: R=30, G=20, B=10, A=255
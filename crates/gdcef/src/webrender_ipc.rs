@@ -1,14 +1,17 @@
 use crate::utils::should_enable_ipc_inspector;
-use cef::{CefStringUtf16, ImplBinaryValue, ImplListValue, ImplProcessMessage, ProcessMessage};
+use cef::{
+    CefStringUtf16, Frame, ImplBinaryValue, ImplListValue, ImplProcessMessage, ProcessMessage,
+};
 use cef_app::ipc_contract::{
-    ROUTE_IME_CARET_POSITION, ROUTE_IPC_BINARY_RENDERER_TO_GODOT, ROUTE_IPC_DATA_RENDERER_TO_GODOT,
-    ROUTE_IPC_RENDERER_TO_GODOT, ROUTE_TRIGGER_IME,
+    ROUTE_ADBLOCK_CLASS_IDS, ROUTE_IME_CARET_POSITION, ROUTE_IPC_BINARY_RENDERER_TO_GODOT,
+    ROUTE_IPC_DATA_RENDERER_TO_GODOT, ROUTE_IPC_RENDERER_TO_GODOT, ROUTE_TRIGGER_IME,
 };
 
 use crate::browser::ImeCompositionRange;
 use crate::webrender::ClientIpcQueues;
 
 pub(crate) fn on_process_message_received(
+    frame: Option<&mut Frame>,
     message: Option<&mut ProcessMessage>,
     ipc: &ClientIpcQueues,
 ) -> i32 {
@@ -119,6 +122,26 @@ pub(crate) fn on_process_message_received(
                 }
             }
         }
+        ROUTE_ADBLOCK_CLASS_IDS => {
+            if let Some(args) = message.argument_list()
+                && let Some(frame) = frame
+                && let Some(adblock_engine) = &ipc.adblock_engine
+            {
+                let split = |index| -> Vec<String> {
+                    CefStringUtf16::from(&args.string(index))
+                        .to_string()
+                        .split_whitespace()
+                        .map(str::to_string)
+                        .collect()
+                };
+                crate::webrender::inject_generic_cosmetic_filters(
+                    adblock_engine,
+                    frame,
+                    &split(0),
+                    &split(1),
+                );
+            }
+        }
         _ => {}
     }
 
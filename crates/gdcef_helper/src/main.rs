@@ -62,7 +62,19 @@ fn main() -> std::process::ExitCode {
 
     let switch = CefString::from("type");
     let is_browser_process = cmd.has_switch(Some(&switch)) != 1;
-    let mut app = cef_app::AppBuilder::build(cef_app::OsrApp::new());
+    // Custom schemes are forwarded by the browser process; see `on_before_child_process_launch`.
+    let custom_schemes_switch = CefString::from(cef_app::CUSTOM_SCHEMES_SWITCH);
+    let custom_schemes = if cmd.has_switch(Some(&custom_schemes_switch)) == 1 {
+        let raw = CefString::from(&cmd.switch_value(Some(&custom_schemes_switch))).to_string();
+        cef_app::parse_custom_schemes(&raw)
+    } else {
+        Vec::new()
+    };
+    let mut app = cef_app::AppBuilder::build(
+        cef_app::OsrApp::builder()
+            .custom_schemes(custom_schemes)
+            .build(),
+    );
     let ret = execute_process(
         Some(args.as_main_args()),
         Some(&mut app),
@@ -46,6 +46,8 @@ export default withMermaid(defineConfig({
                 { text: '方法', link: '/zh_CN/api/methods' },
                 { text: '信号', link: '/zh_CN/api/signals' },
                 { text: 'IPC 检查器', link: '/zh_CN/api/ipc-inspector' },
+                { text: '自定义协议', link: '/zh_CN/api/custom-schemes' },
+                { text: '虚拟文件系统', link: '/zh_CN/api/virtual-fs' },
                 { text: '音频捕获', link: '/zh_CN/api/audio-capture' },
                 { text: '输入法（IME）支持', link: '/zh_CN/api/ime-support' },
                 { text: '拖放', link: '/zh_CN/api/drag-and-drop' },
@@ -94,6 +96,8 @@ export default withMermaid(defineConfig({
             { text: 'Methods', link: '/api/methods' },
             { text: 'Signals', link: '/api/signals' },
             { text: 'IPC Inspector', link: '/api/ipc-inspector' },
+            { text: 'Custom Schemes', link: '/api/custom-schemes' },
+            { text: 'Virtual Filesystem', link: '/api/virtual-fs' },
             { text: 'Audio Capture', link: '/api/audio-capture' },
             { text: 'IME Support', link: '/api/ime-support' },
             { text: 'Drag and Drop', link: '/api/drag-and-drop' },